@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+// Flags either stand alone, like `--strict`, or take a value, like
+// `--ragged=pad`. Value flags are listed with their trailing '='.
+pub fn check_flags(args: &[String], flag_sets: &[&[&str]]) -> Result<(), String> {
+    for arg in args.iter().filter(|arg| arg.starts_with("--")) {
+        let (name, has_value) = match arg.split_once('=') {
+            Some((name, _)) => (name, true),
+            None => (arg.as_str(), false),
+        };
+        let flag = flag_sets.iter()
+            .flat_map(|flags| flags.iter())
+            .find(|flag| flag.trim_end_matches('=') == name)
+            .ok_or(format!("Unknown flag '{}'!", arg))?;
+        match (flag.ends_with('='), has_value) {
+            (true, false) => return Err(format!("Flag {} needs a value, e.g. {}<value>!", name, flag)),
+            (false, true) => return Err(format!("Flag {} does not take a value!", name)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// Value of the first `<flag>=<value>`, where `flag` is given without the '='.
+pub fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, String> {
+    for arg in args {
+        if arg == flag {
+            return Err(format!("Flag {} needs a value, e.g. {}=<value>!", flag, flag));
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')) {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+// Parsed value of `<flag>=<value>`, or `default` if the flag is not given.
+pub fn parse_flag<T: FromStr>(args: &[String], flag: &str, default: T) -> Result<T, String> {
+    match flag_value(args, flag)? {
+        None => Ok(default),
+        Some(value) => value.parse::<T>().map_err(|_err| format!("Could not parse {}={}!", flag, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::{check_flags, flag_value, parse_flag};
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_flags() {
+        let flags: &[&str] = &["--strict", "--ragged="];
+        assert_eq!(check_flags(&to_args(&["input.txt", "--strict", "--ragged=pad"]), &[flags]), Ok(()));
+        assert_eq!(
+            check_flags(&to_args(&["--ragged"]), &[flags]),
+            Err("Flag --ragged needs a value, e.g. --ragged=<value>!".to_string()),
+        );
+        assert_eq!(
+            check_flags(&to_args(&["--strict=yes"]), &[flags]),
+            Err("Flag --strict does not take a value!".to_string()),
+        );
+        assert_eq!(
+            check_flags(&to_args(&["--stritc"]), &[flags, &["--big"]]),
+            Err("Unknown flag '--stritc'!".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_flag_value() {
+        let args = to_args(&["input.txt", "--ragged=pad", "--ragged=blank"]);
+        assert_eq!(flag_value(&args, "--ragged"), Ok(Some("pad")));
+        assert_eq!(flag_value(&args, "--rag"), Ok(None));
+        assert!(flag_value(&to_args(&["--ragged", "pad"]), "--ragged").is_err());
+    }
+
+    #[test]
+    fn test_parse_flag() {
+        let args = to_args(&["--seed=7", "--rate=0.5", "--cards=-1"]);
+        assert_eq!(parse_flag(&args, "--seed", 0u64), Ok(7));
+        assert_eq!(parse_flag(&args, "--rate", 0.0), Ok(0.5));
+        assert_eq!(parse_flag(&args, "--games", 100usize), Ok(100));
+        assert_eq!(parse_flag(&args, "--cards", 0usize), Err("Could not parse --cards=-1!".to_string()));
+    }
+}
@@ -0,0 +1,20 @@
+const BAR_WIDTH: usize = 40;
+
+// `<label> | ### <count>` with the bar scaled so that `max_count` fills the
+// whole width. Any non-zero count gets at least one '#'.
+pub fn bar_line(label: &str, count: usize, max_count: usize, label_width: usize) -> String {
+    let bar_len = if max_count == 0 {0} else {(count * BAR_WIDTH).div_ceil(max_count)};
+    format!("{:>width$} | {} {}", label, "#".repeat(bar_len), count, width = label_width)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::histogram::bar_line;
+
+    #[test]
+    fn test_bar_line() {
+        assert_eq!(bar_line("3", 2, 2, 1), format!("3 | {} 2", "#".repeat(40)));
+        assert_eq!(bar_line("4", 1, 3, 2), format!(" 4 | {} 1", "#".repeat(14)));
+        assert_eq!(bar_line("5", 0, 0, 1), "5 |  0");
+    }
+}
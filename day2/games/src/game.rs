@@ -0,0 +1,105 @@
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColorCounts {
+    pub red_count: usize,
+    pub green_count: usize,
+    pub blue_count: usize,
+}
+
+pub type Handful = ColorCounts;
+
+#[derive(Debug, PartialEq)]
+pub struct Game {
+    pub id: usize,
+    pub handfuls: Vec<Handful>,
+}
+
+pub fn min_color_counts(handfuls: &Vec<Handful>) -> ColorCounts {
+    let mut min_counts = ColorCounts {
+        red_count: 0,
+        green_count: 0,
+        blue_count: 0,
+    };
+    for handful in handfuls {
+        if handful.red_count > min_counts.red_count {
+            min_counts.red_count = handful.red_count
+        }
+        if handful.green_count > min_counts.green_count {
+            min_counts.green_count = handful.green_count
+        }
+        if handful.blue_count > min_counts.blue_count {
+            min_counts.blue_count = handful.blue_count
+        }
+    }
+    min_counts
+}
+
+pub fn parse_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Vec<Game>, String> {
+    lines.enumerate()
+        .map(|(line_idx, line)| parse_line(line)
+             .map_err(|err| format!("Line {}: {}", line_idx + 1, err)))
+        .collect()
+}
+
+pub fn parse_line(line: &str) -> Result<Game, String> {
+    let (id_part, handfuls_part) = line
+        .split_once(": ").ok_or("Line has no ': ' seperator!")?;
+    let id = id_part
+        .strip_prefix("Game ").ok_or("Line does not start with 'Game '!")?
+        .parse::<usize>().map_err(|_err| "Could not parse game id!")?;
+    Ok(Game {id, handfuls: parse_handfuls(handfuls_part)?})
+}
+
+fn parse_handfuls(s: &str) -> Result<Vec<Handful>, String> {
+    let mut handfuls = Vec::new();
+    for handful_str in s.split("; ").collect::<Vec<&str>>() {
+        handfuls.push(parse_handful(handful_str)?);
+    }
+    Ok(handfuls)
+}
+
+fn parse_handful(s: &str) -> Result<Handful, String> {
+    let mut handful = Handful {
+        red_count: 0,
+        green_count: 0,
+        blue_count: 0,
+    };
+    for part in s.split(", ").collect::<Vec<&str>>() {
+        if part.ends_with(" red") {
+            handful.red_count += part
+                .strip_suffix(" red").ok_or("Could not parse red count")?
+                .parse::<usize>().map_err(|_err| "Could not parse red count")?;
+        } else if part.ends_with(" green") {
+            handful.green_count += part
+                .strip_suffix(" green").ok_or("Could not parse green count")?
+                .parse::<usize>().map_err(|_err| "Could not parse green count")?;
+        } else if part.ends_with(" blue") {
+            handful.blue_count += part
+                .strip_suffix(" blue").ok_or("Could not parse blue count")?
+                .parse::<usize>().map_err(|_err| "Could not parse blue count")?;
+        } else {
+            return Err("Handful part must start with 'red ', 'green ' or 'blue '!".to_string());
+        }
+    }
+    Ok(handful)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{parse_line, Game, Handful};
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("Game 6: 3 red, 2 blue; 6 green, 13 blue; 11 blue, 1 red; 4 green, 3 red, 5 blue"),
+            Ok(Game {
+                id: 6,
+                handfuls: vec![
+                    Handful {red_count: 3, green_count: 0, blue_count: 2},
+                    Handful {red_count: 0, green_count: 6, blue_count: 13},
+                    Handful {red_count: 1, green_count: 0, blue_count: 11},
+                    Handful {red_count: 3, green_count: 4, blue_count: 5},
+                ]
+            })
+        );
+    }
+}
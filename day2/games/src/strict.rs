@@ -0,0 +1,143 @@
+use crate::game::{parse_line, Game};
+
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+pub fn parse_lines_strict<'a>(
+    lines: impl Iterator<Item = &'a str>,
+) -> Result<Vec<Game>, Vec<String>> {
+    let mut games = Vec::new();
+    let mut problems = Vec::new();
+    let mut prev_id: Option<usize> = None;
+    for (line_idx, line) in lines.enumerate() {
+        let line_num = line_idx + 1;
+        let mut line_problems = validate_line(line);
+        if let Some(id) = parse_game_id(line) {
+            if let Some(prev_id) = prev_id {
+                if id <= prev_id {
+                    line_problems.push(format!(
+                        "Game id {} does not follow previous game id {}!", id, prev_id,
+                    ));
+                }
+            }
+            prev_id = Some(id);
+        }
+        if !line_problems.is_empty() {
+            for problem in line_problems {
+                problems.push(format!("Line {}: {}", line_num, problem));
+            }
+            continue;
+        }
+        match parse_line(line) {
+            Ok(game) => games.push(game),
+            Err(err) => problems.push(format!("Line {}: {}", line_num, err)),
+        }
+    }
+    if problems.is_empty() {
+        Ok(games)
+    } else {
+        Err(problems)
+    }
+}
+
+fn parse_game_id(line: &str) -> Option<usize> {
+    line.split_once(':')?.0.strip_prefix("Game ")?.parse::<usize>().ok()
+}
+
+fn validate_line(line: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    if line.trim() != line {
+        problems.push("Line has leading or trailing whitespace!".to_string());
+    }
+    if line.contains("  ") || line.contains('\t') {
+        problems.push("Line contains extra whitespace!".to_string());
+    }
+    let Some((id_part, handfuls_part)) = line.split_once(": ") else {
+        problems.push("Line has no ': ' seperator!".to_string());
+        return problems;
+    };
+    match id_part.strip_prefix("Game ") {
+        None => problems.push("Line does not start with 'Game '!".to_string()),
+        Some(id_str) => if !is_plain_number(id_str) {
+            problems.push(format!("Could not parse game id '{}'!", id_str));
+        },
+    }
+    for (handful_idx, handful_str) in handfuls_part.split("; ").enumerate() {
+        for problem in validate_handful(handful_str) {
+            problems.push(format!("Handful {}: {}", handful_idx + 1, problem));
+        }
+    }
+    problems
+}
+
+fn validate_handful(s: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    if s.trim().is_empty() {
+        problems.push("Handful is empty!".to_string());
+        return problems;
+    }
+    let mut seen_colors = Vec::<&str>::new();
+    for part in s.split(", ") {
+        let Some((count_str, color)) = part.split_once(' ') else {
+            problems.push(format!("Could not parse '{}' as '<count> <color>'!", part));
+            continue;
+        };
+        if !is_plain_number(count_str) {
+            problems.push(format!("Could not parse count '{}'!", count_str));
+        } else {
+            match count_str.parse::<usize>() {
+                Ok(0) => problems.push(format!("Count of {} must be greater than zero!", color)),
+                Ok(_) => {}
+                Err(_) => problems.push(format!("Count {} of {} is too large!", count_str, color)),
+            }
+        }
+        if !COLORS.contains(&color) {
+            problems.push(format!("Unknown color '{}'!", color));
+        } else if seen_colors.contains(&color) {
+            problems.push(format!("Color {} appears more than once!", color));
+        } else {
+            seen_colors.push(color);
+        }
+    }
+    problems
+}
+
+fn is_plain_number(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strict::parse_lines_strict;
+
+    #[test]
+    fn test_parse_lines_strict_accepts_valid_games() {
+        let games = parse_lines_strict([
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+        ].into_iter()).unwrap();
+        assert_eq!(games.iter().map(|game| game.id).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_parse_lines_strict_reports_every_problem() {
+        assert_eq!(
+            parse_lines_strict([
+                "Game 1: 3 red, 2 red; 0 blue",
+                "Game 2: 1 blue; ; 2 green",
+                "Game 2: 1 blue",
+                "Game 4: 1  blue ",
+                "Game 5: 99999999999999999999 red",
+            ].into_iter()),
+            Err(vec![
+                "Line 1: Handful 1: Color red appears more than once!".to_string(),
+                "Line 1: Handful 2: Count of blue must be greater than zero!".to_string(),
+                "Line 2: Handful 2: Handful is empty!".to_string(),
+                "Line 3: Game id 2 does not follow previous game id 2!".to_string(),
+                "Line 4: Line has leading or trailing whitespace!".to_string(),
+                "Line 4: Line contains extra whitespace!".to_string(),
+                "Line 4: Handful 1: Unknown color ' blue '!".to_string(),
+                "Line 5: Handful 1: Count 99999999999999999999 of red is too large!".to_string(),
+            ]),
+        );
+    }
+}
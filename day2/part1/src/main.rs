@@ -1,19 +1,13 @@
 use std::{io::{self, BufRead}, env, fs::File};
 
-#[derive(Debug, PartialEq)]
-struct ColorCounts {
-    red_count: usize,
-    green_count: usize,
-    blue_count: usize,
-}
-
-type Handful = ColorCounts;
+use common::args::check_flags;
+use games::{
+    game::{parse_lines, ColorCounts, Game, Handful}, strict::parse_lines_strict,
+};
 
-#[derive(Debug, PartialEq)]
-struct Game {
-    id: usize,
-    handfuls: Vec<Handful>,
-}
+mod query;
+mod simulate;
+mod solve;
 
 fn main() -> Result<(), String> {
     let max_color_counts = ColorCounts {
@@ -22,12 +16,64 @@ fn main() -> Result<(), String> {
         blue_count: 14,
     };
 
-    let file_path = env::args().nth(1).ok_or("No file provided!")?;
-    let file = File::open(file_path).map_err(|err| err.to_string())?;
-    let file_lines = io::BufReader::new(file).lines()
-        .map(|l| l.unwrap());
-    let sum_possible_game_ids: usize =
-        file_lines.map(|l| parse_line(l.as_str()).unwrap())
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "simulate") {
+        return run_simulate(&args[1..]);
+    }
+    check_flags(&args, &[&["--strict", "--by-id-sum", "--brute-force"]])?;
+    let strict = args.iter().any(|arg| arg == "--strict");
+    let mut positional_args = args.iter().filter(|arg| !arg.starts_with("--"));
+    let first_arg = positional_args.next().ok_or("No file provided!")?;
+    if first_arg == "query" {
+        let expr = query::parse_query(
+            positional_args.next().ok_or("No query expression provided!")?
+        )?;
+        let file_path = positional_args.next().ok_or("No file provided!")?;
+        let games = read_games(file_path, strict)?;
+        let result = query::run_query(&expr, &games);
+        for id in &result.ids {
+            println!("{}", id);
+        }
+        println!("Number of matching games: {}", result.ids.len());
+        println!("Sum of IDs of matching games: {}", result.id_sum);
+        return Ok(());
+    }
+    if first_arg == "solve" {
+        let budget = positional_args.next().ok_or("No cube budget provided!")?
+            .parse::<usize>().map_err(|_err| "Could not parse cube budget!")?;
+        let file_path = positional_args.next().ok_or("No file provided!")?;
+        let objective = if args.iter().any(|arg| arg == "--by-id-sum") {
+            solve::Objective::IdSum
+        } else {
+            solve::Objective::GameCount
+        };
+        let games = read_games(file_path, strict)?;
+        let requirements = solve::rgb_requirements(&games, objective);
+        let solution = if args.iter().any(|arg| arg == "--brute-force") {
+            solve::solve_brute_force(&requirements, 3, budget)
+        } else {
+            solve::solve(&requirements, 3, budget)
+        };
+        let bag = ColorCounts {
+            red_count: solution.limits[0],
+            green_count: solution.limits[1],
+            blue_count: solution.limits[2],
+        };
+        let possible_games: Vec<&Game> = games.iter()
+            .filter(|game| is_possible_game(&bag, game))
+            .collect();
+        println!("Bag: {} red, {} green, {} blue", bag.red_count, bag.green_count, bag.blue_count);
+        println!("Total cubes: {}", solution.cost);
+        println!("Number of possible games: {}", possible_games.len());
+        println!(
+            "Sum of IDs of possible games: {}",
+            possible_games.iter().map(|game| game.id).sum::<usize>(),
+        );
+        return Ok(());
+    }
+
+    let games = read_games(first_arg, strict)?;
+    let sum_possible_game_ids: usize = games.iter()
         .filter(|game| is_possible_game(&max_color_counts, game))
         .map(|game| game.id)
         .sum();
@@ -35,11 +81,34 @@ fn main() -> Result<(), String> {
     Ok(())
 }
 
+fn run_simulate(args: &[String]) -> Result<(), String> {
+    check_flags(args, &[simulate::SIMULATE_FLAGS])?;
+    for line in simulate::simulate(&simulate::SimulationConfig::from_args(args)?)? {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn read_games(file_path: &str, strict: bool) -> Result<Vec<Game>, String> {
+    let file = File::open(file_path).map_err(|err| err.to_string())?;
+    let file_lines = io::BufReader::new(file).lines()
+        .collect::<Result<Vec<String>, _>>().map_err(|err| err.to_string())?;
+    if !strict {
+        return parse_lines(file_lines.iter().map(|l| l.as_str()));
+    }
+    parse_lines_strict(file_lines.iter().map(|l| l.as_str())).map_err(|problems| {
+        for problem in &problems {
+            eprintln!("{}", problem);
+        }
+        format!("Found {} problem(s) in strict mode!", problems.len())
+    })
+}
+
 fn is_possible_game(
     max_color_counts: &ColorCounts, 
     game: &Game
 ) -> bool {
-    return game.handfuls.iter()
+    game.handfuls.iter()
         .all(|handful| is_possible_handful(max_color_counts, handful))
 }
 
@@ -51,67 +120,3 @@ fn is_possible_handful(
         && handful.green_count  <= max_color_counts.green_count 
         && handful.blue_count   <= max_color_counts.blue_count
 }
-
-fn parse_line(line: &str) -> Result<Game, String> {
-    let (id_part, handfuls_part) = line
-        .split_once(": ").ok_or("Line has no ': ' seperator!")?;
-    let id = id_part
-        .strip_prefix("Game ").ok_or("Line does not start with 'Game '!")?
-        .parse::<usize>().map_err(|_err| "Could not parse game id!")?;
-    Ok(Game {id, handfuls: parse_handfuls(handfuls_part)?})
-}
-
-fn parse_handfuls(s: &str) -> Result<Vec<Handful>, String> {
-    let mut handfuls = Vec::new();
-    for handful_str in s.split("; ").collect::<Vec<&str>>() {
-        handfuls.push(parse_handful(handful_str)?);
-    }
-    Ok(handfuls)
-}
-
-fn parse_handful(s: &str) -> Result<Handful, String> {
-    let mut handful = Handful {
-        red_count: 0,
-        green_count: 0,
-        blue_count: 0,
-    };
-    for part in s.split(", ").collect::<Vec<&str>>() {
-        if part.ends_with(" red") {
-            handful.red_count += part
-                .strip_suffix(" red").ok_or("Could not parse red count")?
-                .parse::<usize>().map_err(|_err| "Could not parse red count")?;
-        } else if part.ends_with(" green") {
-            handful.green_count += part
-                .strip_suffix(" green").ok_or("Could not parse green count")?
-                .parse::<usize>().map_err(|_err| "Could not parse green count")?;
-        } else if part.ends_with(" blue") {
-            handful.blue_count += part
-                .strip_suffix(" blue").ok_or("Could not parse blue count")?
-                .parse::<usize>().map_err(|_err| "Could not parse blue count")?;
-        } else {
-            return Err("Handful part must start with 'red ', 'green ' or 'blue '!".to_string());
-        }
-    } 
-    Ok(handful)
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::{parse_line, Game, Handful};
-
-    #[test]
-    fn test_parse_line() {
-        assert_eq!(
-            parse_line("Game 6: 3 red, 2 blue; 6 green, 13 blue; 11 blue, 1 red; 4 green, 3 red, 5 blue"),
-            Ok(Game {
-                id: 6,
-                handfuls: vec![
-                    Handful {red_count: 3, green_count: 0, blue_count: 2},
-                    Handful {red_count: 0, green_count: 6, blue_count: 13},
-                    Handful {red_count: 1, green_count: 0, blue_count: 11},
-                    Handful {red_count: 3, green_count: 4, blue_count: 5},
-                ]
-            })
-        );
-    }
-}
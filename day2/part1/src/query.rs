@@ -0,0 +1,333 @@
+use games::game::{Game, Handful};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Aggregate {
+    Max,
+    Min,
+    Sum,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Value {
+    Num(usize),
+    Id,
+    HandfulCount,
+    Color(Color),
+    Aggregate(Aggregate, Color),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Any(Box<Expr>),
+    All(Box<Expr>),
+    Cmp(Value, CmpOp, Value),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Num(usize),
+    Ident(String),
+    Op(CmpOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+pub struct QueryResult {
+    pub ids: Vec<usize>,
+    pub id_sum: usize,
+}
+
+pub fn run_query(expr: &Expr, games: &[Game]) -> QueryResult {
+    let ids: Vec<usize> = games.iter()
+        .filter(|game| eval(expr, game, None))
+        .map(|game| game.id)
+        .collect();
+    let id_sum = ids.iter().sum();
+    QueryResult {ids, id_sum}
+}
+
+pub fn parse_query(s: &str) -> Result<Expr, String> {
+    let tokens = tokenize(s)?;
+    let mut parser = Parser {tokens, pos: 0, handful_scope: 0};
+    let expr = parser.parse_or()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(expr),
+        Some(token) => Err(format!("Unexpected token {:?} after end of expression!", token)),
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let num_str: String = chars[start..i].iter().collect();
+            let num = num_str.parse::<usize>().map_err(|_err| format!("Number {} is too large!", num_str))?;
+            tokens.push(Token::Num(num));
+            continue;
+        }
+        if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            continue;
+        }
+        let (token, len) = match (c, next) {
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('<', Some('=')) => (Token::Op(CmpOp::Le), 2),
+            ('>', Some('=')) => (Token::Op(CmpOp::Ge), 2),
+            ('=', Some('=')) => (Token::Op(CmpOp::Eq), 2),
+            ('!', Some('=')) => (Token::Op(CmpOp::Ne), 2),
+            ('<', _) => (Token::Op(CmpOp::Lt), 1),
+            ('>', _) => (Token::Op(CmpOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            _ => return Err(format!("Unexpected character '{}' at position {}!", c, i)),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    // Colors on their own refer to the current handful, so they are only
+    // allowed inside `any(...)` or `all(...)`.
+    handful_scope: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("Unexpected end of expression!")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        let token = self.next()?;
+        if token != expected {
+            return Err(format!("Expected {:?}, found {:?}!", expected, token));
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) if name == "any" || name == "all" => {
+                let is_any = name == "any";
+                self.pos += 1;
+                self.expect(Token::LParen)?;
+                self.handful_scope += 1;
+                let expr = self.parse_or()?;
+                self.handful_scope -= 1;
+                self.expect(Token::RParen)?;
+                Ok(if is_any {Expr::Any(Box::new(expr))} else {Expr::All(Box::new(expr))})
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let left = self.parse_value()?;
+        let op = match self.next()? {
+            Token::Op(op) => op,
+            token => return Err(format!("Expected comparison operator, found {:?}!", token)),
+        };
+        let right = self.parse_value()?;
+        Ok(Expr::Cmp(left, op, right))
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        let name = match self.next()? {
+            Token::Num(num) => return Ok(Value::Num(num)),
+            Token::Ident(name) => name,
+            token => return Err(format!("Expected value, found {:?}!", token)),
+        };
+        if let Some(color) = parse_color(&name) {
+            if self.handful_scope == 0 {
+                return Err(format!(
+                    "Color '{}' can only be used inside any(...) or all(...)!", name
+                ));
+            }
+            return Ok(Value::Color(color));
+        }
+        let aggregate = match name.as_str() {
+            "id" => return Ok(Value::Id),
+            "handfuls" => return Ok(Value::HandfulCount),
+            "max" => Aggregate::Max,
+            "min" => Aggregate::Min,
+            "sum" => Aggregate::Sum,
+            _ => return Err(format!("Unknown identifier '{}'!", name)),
+        };
+        self.expect(Token::LParen)?;
+        let color = match self.next()? {
+            Token::Ident(color_name) => parse_color(&color_name)
+                .ok_or(format!("Unknown color '{}'!", color_name))?,
+            token => return Err(format!("Expected color, found {:?}!", token)),
+        };
+        self.expect(Token::RParen)?;
+        Ok(Value::Aggregate(aggregate, color))
+    }
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    match name {
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "blue" => Some(Color::Blue),
+        _ => None,
+    }
+}
+
+fn color_count(handful: &Handful, color: Color) -> usize {
+    match color {
+        Color::Red => handful.red_count,
+        Color::Green => handful.green_count,
+        Color::Blue => handful.blue_count,
+    }
+}
+
+fn eval(expr: &Expr, game: &Game, handful: Option<&Handful>) -> bool {
+    match expr {
+        Expr::Or(left, right) => eval(left, game, handful) || eval(right, game, handful),
+        Expr::And(left, right) => eval(left, game, handful) && eval(right, game, handful),
+        Expr::Not(inner) => !eval(inner, game, handful),
+        Expr::Any(inner) => game.handfuls.iter().any(|h| eval(inner, game, Some(h))),
+        Expr::All(inner) => game.handfuls.iter().all(|h| eval(inner, game, Some(h))),
+        Expr::Cmp(left, op, right) => {
+            let left = eval_value(left, game, handful);
+            let right = eval_value(right, game, handful);
+            match op {
+                CmpOp::Lt => left < right,
+                CmpOp::Le => left <= right,
+                CmpOp::Gt => left > right,
+                CmpOp::Ge => left >= right,
+                CmpOp::Eq => left == right,
+                CmpOp::Ne => left != right,
+            }
+        }
+    }
+}
+
+fn eval_value(value: &Value, game: &Game, handful: Option<&Handful>) -> usize {
+    match value {
+        Value::Num(num) => *num,
+        Value::Id => game.id,
+        Value::HandfulCount => game.handfuls.len(),
+        // The parser only accepts bare colors inside a handful scope.
+        Value::Color(color) => handful.map_or(0, |h| color_count(h, *color)),
+        Value::Aggregate(aggregate, color) => {
+            let counts = game.handfuls.iter().map(|h| color_count(h, *color));
+            match aggregate {
+                Aggregate::Max => counts.max().unwrap_or(0),
+                Aggregate::Min => counts.min().unwrap_or(0),
+                Aggregate::Sum => counts.sum(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use games::game::parse_line;
+
+    use crate::query::{parse_query, run_query};
+
+    #[test]
+    fn test_run_query() {
+        let games = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ].iter().map(|l| parse_line(l).unwrap()).collect::<Vec<_>>();
+        let query = |s: &str| run_query(&parse_query(s).unwrap(), &games).ids;
+
+        assert_eq!(query("any(red > 10) && max(blue) < 10"), vec![3]);
+        assert_eq!(query("all(red <= 12 && green <= 13 && blue <= 14)"), vec![1, 2, 5]);
+        assert_eq!(query("!(handfuls == 3) || id == 1"), vec![1, 5]);
+        assert_eq!(query("sum(green) >= 5 && min(red) != 0"), vec![3, 4, 5]);
+        assert_eq!(run_query(&parse_query("max(red) > 5").unwrap(), &games).id_sum, 12);
+    }
+
+    #[test]
+    fn test_parse_query_errors() {
+        assert!(parse_query("red > 10").is_err());
+        assert!(parse_query("max(purple) > 1").is_err());
+        assert!(parse_query("any(red > 1").is_err());
+        assert!(parse_query("id > 1 id").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use common::{args::{flag_value, parse_flag}, rng::Rng};
+use games::game::{parse_line, ColorCounts, Handful};
+
+use crate::is_possible_game;
+
+pub const SIMULATE_FLAGS: &[&str] = &["--bag=", "--games=", "--handfuls=", "--seed=", "--without-replacement"];
+
+pub struct SimulationConfig {
+    pub bag: ColorCounts,
+    pub games: usize,
+    pub handfuls: usize,
+    pub seed: u64,
+    // With replacement every handful is put back before the next one is drawn,
+    // as in the puzzle. Without it the handfuls of one game all come out of
+    // the bag together.
+    pub with_replacement: bool,
+}
+
+impl SimulationConfig {
+    // Reads `--bag=<red>,<green>,<blue>`, `--games=`, `--handfuls=`, `--seed=`
+    // and `--without-replacement`, defaulting to the puzzle's bag.
+    pub fn from_args(args: &[String]) -> Result<SimulationConfig, String> {
+        let bag = match flag_value(args, "--bag")? {
+            None => ColorCounts {red_count: 12, green_count: 13, blue_count: 14},
+            Some(value) => {
+                let counts = value.split(',')
+                    .map(|count| count.parse::<usize>())
+                    .collect::<Result<Vec<usize>, _>>()
+                    .map_err(|_err| format!("Could not parse --bag={}!", value))?;
+                let [red_count, green_count, blue_count] = counts[..] else {
+                    return Err("Bag must be given as --bag=<red>,<green>,<blue>!".to_string());
+                };
+                ColorCounts {red_count, green_count, blue_count}
+            }
+        };
+        Ok(SimulationConfig {
+            bag,
+            games: parse_flag(args, "--games", 100)?,
+            handfuls: parse_flag(args, "--handfuls", 3)?,
+            seed: parse_flag(args, "--seed", 0)?,
+            with_replacement: !args.iter().any(|arg| arg == "--without-replacement"),
+        })
+    }
+}
+
+pub fn simulate(config: &SimulationConfig) -> Result<Vec<String>, String> {
+    let bag_size = config.bag.red_count + config.bag.green_count + config.bag.blue_count;
+    if config.handfuls == 0 {
+        return Err("Games need at least one handful!".to_string());
+    }
+    if bag_size == 0 || (!config.with_replacement && bag_size < config.handfuls) {
+        return Err(format!(
+            "Bag of {} cubes is too small for {} handfuls!", bag_size, config.handfuls,
+        ));
+    }
+    let mut rng = Rng::new(config.seed);
+    let mut lines = Vec::new();
+    for id in 1..=config.games {
+        let mut remaining = config.bag.clone();
+        let mut handful_strs = Vec::new();
+        for handful_idx in 0..config.handfuls {
+            let remaining_size = remaining.red_count + remaining.green_count + remaining.blue_count;
+            // Leave at least one cube for every handful still to be drawn.
+            let max_size = if config.with_replacement {
+                remaining_size
+            } else {
+                remaining_size - (config.handfuls - handful_idx - 1)
+            };
+            let size = rng.range_inclusive(1, max_size);
+            let handful = draw_handful(&mut rng, &remaining, size);
+            if !config.with_replacement {
+                remaining.red_count -= handful.red_count;
+                remaining.green_count -= handful.green_count;
+                remaining.blue_count -= handful.blue_count;
+            }
+            handful_strs.push(format_handful(&mut rng, &handful));
+        }
+        let line = format!("Game {}: {}", id, handful_strs.join("; "));
+        let game = parse_line(&line)?;
+        if !is_possible_game(&config.bag, &game) {
+            return Err(format!("Simulated game {} is not possible with the bag!", id));
+        }
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+fn draw_handful(rng: &mut Rng, bag: &ColorCounts, size: usize) -> Handful {
+    let mut left = bag.clone();
+    let mut handful = Handful {red_count: 0, green_count: 0, blue_count: 0};
+    for _ in 0..size {
+        let pick = rng.below(left.red_count + left.green_count + left.blue_count);
+        if pick < left.red_count {
+            left.red_count -= 1;
+            handful.red_count += 1;
+        } else if pick < left.red_count + left.green_count {
+            left.green_count -= 1;
+            handful.green_count += 1;
+        } else {
+            left.blue_count -= 1;
+            handful.blue_count += 1;
+        }
+    }
+    handful
+}
+
+fn format_handful(rng: &mut Rng, handful: &Handful) -> String {
+    let mut parts: Vec<String> = [
+        (handful.red_count, "red"),
+        (handful.green_count, "green"),
+        (handful.blue_count, "blue"),
+    ].iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, color)| format!("{} {}", count, color))
+        .collect();
+    // Shuffle so the color order varies like in real game logs.
+    for i in (1..parts.len()).rev() {
+        parts.swap(i, rng.below(i + 1));
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use games::{game::ColorCounts, strict::parse_lines_strict};
+
+    use crate::simulate::{simulate, SimulationConfig};
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_simulate() {
+        for with_replacement in [true, false] {
+            let config = SimulationConfig {
+                bag: ColorCounts {red_count: 12, green_count: 13, blue_count: 14},
+                games: 200,
+                handfuls: 5,
+                seed: 7,
+                with_replacement,
+            };
+            let lines = simulate(&config).unwrap();
+            assert_eq!(lines, simulate(&config).unwrap());
+            let games = parse_lines_strict(lines.iter().map(|l| l.as_str())).unwrap();
+            assert_eq!(games.len(), 200);
+            assert!(games.iter().all(|game| game.handfuls.len() == 5));
+            if !with_replacement {
+                assert!(games.iter().all(|game| {
+                    game.handfuls.iter().map(|h| h.red_count).sum::<usize>() <= 12
+                        && game.handfuls.iter().map(|h| h.green_count).sum::<usize>() <= 13
+                        && game.handfuls.iter().map(|h| h.blue_count).sum::<usize>() <= 14
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn test_simulation_config_from_args() {
+        let args = to_args(&["--bag=2,2,2", "--games=5", "--seed=18446744073709551615"]);
+        let config = SimulationConfig::from_args(&args).unwrap();
+        assert_eq!(config.bag, ColorCounts {red_count: 2, green_count: 2, blue_count: 2});
+        assert_eq!((config.games, config.handfuls, config.seed, config.with_replacement), (5, 3, u64::MAX, true));
+        assert!(SimulationConfig::from_args(&to_args(&["--bag=2,2"])).is_err());
+        assert!(SimulationConfig::from_args(&to_args(&["--games"])).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use games::game::{min_color_counts, Game};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Objective {
+    GameCount,
+    IdSum,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Requirement {
+    weight: usize,
+    min_counts: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Solution {
+    pub limits: Vec<usize>,
+    pub score: usize,
+    pub cost: usize,
+}
+
+impl Solution {
+    fn is_better_than(&self, other: &Solution) -> bool {
+        self.score > other.score || (self.score == other.score && self.cost < other.cost)
+    }
+}
+
+pub fn rgb_requirements(games: &[Game], objective: Objective) -> Vec<Requirement> {
+    games.iter().map(|game| {
+        let min_counts = min_color_counts(&game.handfuls);
+        Requirement {
+            weight: match objective {
+                Objective::GameCount => 1,
+                Objective::IdSum => game.id,
+            },
+            min_counts: vec![min_counts.red_count, min_counts.green_count, min_counts.blue_count],
+        }
+    }).collect()
+}
+
+// A game only constrains each color from below, so every optimal bag can be
+// lowered color by color onto one of the games' minimum counts (or zero)
+// without losing a game. The last color is then set to the largest minimum
+// count that still fits the remaining budget, which admits every game that
+// can still be afforded.
+pub fn solve(requirements: &[Requirement], palette_size: usize, budget: usize) -> Solution {
+    let mut best = Solution {limits: vec![0; palette_size], score: 0, cost: 0};
+    let reqs: Vec<&Requirement> = requirements.iter().collect();
+    if palette_size == 0 {
+        best.score = reqs.iter().map(|r| r.weight).sum();
+        return best;
+    }
+    let mut limits = Vec::with_capacity(palette_size);
+    search(&reqs, palette_size, budget, &mut limits, &mut best);
+    best
+}
+
+fn search(
+    reqs: &[&Requirement],
+    palette_size: usize,
+    budget_left: usize,
+    limits: &mut Vec<usize>,
+    best: &mut Solution,
+) {
+    let color = limits.len();
+    let cost: usize = limits.iter().sum();
+    if color == palette_size - 1 {
+        let affordable: Vec<&&Requirement> = reqs.iter()
+            .filter(|r| r.min_counts[color] <= budget_left)
+            .collect();
+        let limit = affordable.iter().map(|r| r.min_counts[color]).max().unwrap_or(0);
+        let mut candidate_limits = limits.clone();
+        candidate_limits.push(limit);
+        let candidate = Solution {
+            limits: candidate_limits,
+            score: affordable.iter().map(|r| r.weight).sum(),
+            cost: cost + limit,
+        };
+        if candidate.is_better_than(best) {
+            *best = candidate;
+        }
+        return;
+    }
+
+    let mut thresholds: Vec<usize> = reqs.iter()
+        .map(|r| r.min_counts[color])
+        .filter(|count| *count <= budget_left)
+        .collect();
+    thresholds.push(0);
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    for threshold in thresholds {
+        let remaining: Vec<&Requirement> = reqs.iter()
+            .filter(|r| r.min_counts[color] <= threshold)
+            .copied()
+            .collect();
+        limits.push(threshold);
+        search(&remaining, palette_size, budget_left - threshold, limits, best);
+        limits.pop();
+    }
+}
+
+pub fn solve_brute_force(requirements: &[Requirement], palette_size: usize, budget: usize) -> Solution {
+    let mut best = Solution {limits: vec![0; palette_size], score: 0, cost: 0};
+    let mut limits = vec![0; palette_size];
+    best.score = score(requirements, &limits);
+    loop {
+        // Step to the next limits vector with a total of at most `budget`.
+        let mut color = 0;
+        loop {
+            if color == palette_size {
+                return best;
+            }
+            limits[color] += 1;
+            if limits.iter().sum::<usize>() <= budget {
+                break;
+            }
+            limits[color] = 0;
+            color += 1;
+        }
+        let candidate = Solution {
+            limits: limits.clone(),
+            score: score(requirements, &limits),
+            cost: limits.iter().sum(),
+        };
+        if candidate.is_better_than(&best) {
+            best = candidate;
+        }
+    }
+}
+
+fn score(requirements: &[Requirement], limits: &[usize]) -> usize {
+    requirements.iter()
+        .filter(|r| r.min_counts.iter().zip(limits).all(|(count, limit)| count <= limit))
+        .map(|r| r.weight)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+    use games::game::parse_line;
+
+    use crate::solve::{rgb_requirements, solve, solve_brute_force, Objective, Requirement};
+
+    #[test]
+    fn test_solve() {
+        let games = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ].iter().map(|l| parse_line(l).unwrap()).collect::<Vec<_>>();
+
+        let solution = solve(&rgb_requirements(&games, Objective::GameCount), 3, 12);
+        assert_eq!(solution.limits, vec![1, 3, 4]);
+        assert_eq!(solution.score, 1);
+
+        let solution = solve(&rgb_requirements(&games, Objective::GameCount), 3, 13);
+        assert_eq!(solution.score, 2);
+        assert_eq!(solution.cost, 13);
+
+        let solution = solve(&rgb_requirements(&games, Objective::IdSum), 3, 39);
+        assert_eq!(solution.limits, vec![14, 3, 15]);
+        assert_eq!(solution.score, 1 + 2 + 4 + 5);
+    }
+
+    #[test]
+    fn test_solve_matches_brute_force() {
+        let mut rng = Rng::new(2023);
+        let mut next = |bound: usize| rng.below(bound);
+        for _ in 0..200 {
+            let palette_size = 1 + next(3);
+            let budget = next(12);
+            let requirements: Vec<Requirement> = (0..next(6))
+                .map(|_| Requirement {
+                    weight: 1 + next(5),
+                    min_counts: (0..palette_size).map(|_| next(6)).collect(),
+                })
+                .collect();
+            let exact = solve(&requirements, palette_size, budget);
+            let brute_force = solve_brute_force(&requirements, palette_size, budget);
+            assert_eq!((exact.score, exact.cost), (brute_force.score, brute_force.cost));
+        }
+    }
+}
@@ -1,116 +1,48 @@
 use std::{io::{self, BufRead}, env, fs::File};
 
-#[derive(Debug, PartialEq)]
-struct ColorCounts {
-    red_count: usize,
-    green_count: usize,
-    blue_count: usize,
-}
-
-type Handful = ColorCounts;
+use common::args::check_flags;
+use games::{
+    game::{min_color_counts, parse_lines, ColorCounts, Game}, strict::parse_lines_strict,
+};
 
-#[derive(Debug, PartialEq)]
-struct Game {
-    id: usize,
-    handfuls: Vec<Handful>,
-}
+mod stats;
 
 fn main() -> Result<(), String> {
-    let file_path = env::args().nth(1).ok_or("No file provided!")?;
-    let file = File::open(file_path).map_err(|err| err.to_string())?;
-    let file_lines = io::BufReader::new(file).lines()
-        .map(|l| l.unwrap());
-    let sum_sets_powers: usize =
-        file_lines.map(|l| parse_line(l.as_str()).unwrap())
-        .map(|game| min_color_counts(&game.handfuls))
-        .map(|min_color_counts| 
-             min_color_counts.red_count*min_color_counts.green_count*min_color_counts.blue_count
-        )
+    let args: Vec<String> = env::args().skip(1).collect();
+    check_flags(&args, &[&["--strict"]])?;
+    let strict = args.iter().any(|arg| arg == "--strict");
+    let mut positional_args = args.iter().filter(|arg| !arg.starts_with("--"));
+    let first_arg = positional_args.next().ok_or("No file provided!")?;
+    if first_arg == "stats" {
+        let file_path = positional_args.next().ok_or("No file provided!")?;
+        let games = read_games(file_path, strict)?;
+        println!("{}", stats::report(&games));
+        return Ok(());
+    }
+
+    let games = read_games(first_arg, strict)?;
+    let sum_sets_powers: usize = games.iter()
+        .map(|game| power(&min_color_counts(&game.handfuls)))
         .sum();
     println!("Sum of the power fo the sets : {}", sum_sets_powers);
     Ok(())
 }
 
-fn min_color_counts(handfuls: &Vec<Handful>) -> ColorCounts {
-    let mut min_counts = ColorCounts {
-        red_count: 0,
-        green_count: 0,
-        blue_count: 0,
-    };
-    for handful in handfuls {
-        if handful.red_count > min_counts.red_count {
-            min_counts.red_count = handful.red_count
-        }
-        if handful.green_count > min_counts.green_count {
-            min_counts.green_count = handful.green_count
-        }
-        if handful.blue_count > min_counts.blue_count {
-            min_counts.blue_count = handful.blue_count
-        }
-    }
-    min_counts
-}
-
-fn parse_line(line: &str) -> Result<Game, String> {
-    let (id_part, handfuls_part) = line
-        .split_once(": ").ok_or("Line has no ': ' seperator!")?;
-    let id = id_part
-        .strip_prefix("Game ").ok_or("Line does not start with 'Game '!")?
-        .parse::<usize>().map_err(|_err| "Could not parse game id!")?;
-    Ok(Game {id, handfuls: parse_handfuls(handfuls_part)?})
-}
-
-fn parse_handfuls(s: &str) -> Result<Vec<Handful>, String> {
-    let mut handfuls = Vec::new();
-    for handful_str in s.split("; ").collect::<Vec<&str>>() {
-        handfuls.push(parse_handful(handful_str)?);
+fn read_games(file_path: &str, strict: bool) -> Result<Vec<Game>, String> {
+    let file = File::open(file_path).map_err(|err| err.to_string())?;
+    let file_lines = io::BufReader::new(file).lines()
+        .collect::<Result<Vec<String>, _>>().map_err(|err| err.to_string())?;
+    if !strict {
+        return parse_lines(file_lines.iter().map(|l| l.as_str()));
     }
-    Ok(handfuls)
-}
-
-fn parse_handful(s: &str) -> Result<Handful, String> {
-    let mut handful = Handful {
-        red_count: 0,
-        green_count: 0,
-        blue_count: 0,
-    };
-    for part in s.split(", ").collect::<Vec<&str>>() {
-        if part.ends_with(" red") {
-            handful.red_count += part
-                .strip_suffix(" red").ok_or("Could not parse red count")?
-                .parse::<usize>().map_err(|_err| "Could not parse red count")?;
-        } else if part.ends_with(" green") {
-            handful.green_count += part
-                .strip_suffix(" green").ok_or("Could not parse green count")?
-                .parse::<usize>().map_err(|_err| "Could not parse green count")?;
-        } else if part.ends_with(" blue") {
-            handful.blue_count += part
-                .strip_suffix(" blue").ok_or("Could not parse blue count")?
-                .parse::<usize>().map_err(|_err| "Could not parse blue count")?;
-        } else {
-            return Err("Handful part must start with 'red ', 'green ' or 'blue '!".to_string());
+    parse_lines_strict(file_lines.iter().map(|l| l.as_str())).map_err(|problems| {
+        for problem in &problems {
+            eprintln!("{}", problem);
         }
-    } 
-    Ok(handful)
+        format!("Found {} problem(s) in strict mode!", problems.len())
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{parse_line, Game, Handful};
-
-    #[test]
-    fn test_parse_line() {
-        assert_eq!(
-            parse_line("Game 6: 3 red, 2 blue; 6 green, 13 blue; 11 blue, 1 red; 4 green, 3 red, 5 blue"),
-            Ok(Game {
-                id: 6,
-                handfuls: vec![
-                    Handful {red_count: 3, green_count: 0, blue_count: 2},
-                    Handful {red_count: 0, green_count: 6, blue_count: 13},
-                    Handful {red_count: 1, green_count: 0, blue_count: 11},
-                    Handful {red_count: 3, green_count: 4, blue_count: 5},
-                ]
-            })
-        );
-    }
+fn power(color_counts: &ColorCounts) -> usize {
+    color_counts.red_count*color_counts.green_count*color_counts.blue_count
 }
@@ -0,0 +1,153 @@
+use common::histogram::bar_line;
+use games::game::{min_color_counts, Game, Handful};
+
+use crate::power;
+
+const MAX_HISTOGRAM_BUCKETS: usize = 10;
+
+type ColorGetter = fn(&Handful) -> usize;
+
+#[derive(Debug, PartialEq)]
+struct Summary {
+    count: usize,
+    min: usize,
+    max: usize,
+    mean: f64,
+    median: f64,
+}
+
+#[derive(Debug, PartialEq)]
+struct Bucket {
+    start: usize,
+    end: usize,
+    count: usize,
+}
+
+pub fn report(games: &[Game]) -> String {
+    let mut lines = vec![format!("Games: {}", games.len())];
+    let handfuls: Vec<&Handful> = games.iter().flat_map(|game| &game.handfuls).collect();
+    lines.push(format!("Handfuls: {}", handfuls.len()));
+
+    let color_getters: [(&str, ColorGetter); 3] = [
+        ("red", |h| h.red_count),
+        ("green", |h| h.green_count),
+        ("blue", |h| h.blue_count),
+    ];
+    for (color, get_count) in color_getters {
+        // A handful that does not mention a color is not counted as a zero draw.
+        let counts: Vec<usize> = handfuls.iter()
+            .map(|h| get_count(h))
+            .filter(|count| *count > 0)
+            .collect();
+        lines.push(String::new());
+        lines.push(format!("{} count per handful:", color));
+        push_distribution(&mut lines, &counts);
+    }
+
+    let handful_counts: Vec<usize> = games.iter().map(|game| game.handfuls.len()).collect();
+    lines.push(String::new());
+    lines.push("Handfuls per game:".to_string());
+    push_distribution(&mut lines, &handful_counts);
+
+    let powers: Vec<usize> = games.iter()
+        .map(|game| power(&min_color_counts(&game.handfuls)))
+        .collect();
+    lines.push(String::new());
+    lines.push("Power per game:".to_string());
+    push_distribution(&mut lines, &powers);
+
+    lines.join("\n")
+}
+
+fn push_distribution(lines: &mut Vec<String>, values: &[usize]) {
+    let Some(summary) = summarize(values) else {
+        lines.push("  (no values)".to_string());
+        return;
+    };
+    lines.push(format!(
+        "  n={} min={} max={} mean={:.2} median={:.1}",
+        summary.count, summary.min, summary.max, summary.mean, summary.median,
+    ));
+    for line in render_histogram(&histogram(values)) {
+        lines.push(format!("  {}", line));
+    }
+}
+
+fn summarize(values: &[usize]) -> Option<Summary> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let count = sorted.len();
+    let min = *sorted.first()?;
+    let max = *sorted.last()?;
+    let mean = sorted.iter().sum::<usize>() as f64 / count as f64;
+    let median = if count % 2 == 1 {
+        sorted[count / 2] as f64
+    } else {
+        (sorted[count / 2 - 1] + sorted[count / 2]) as f64 / 2.0
+    };
+    Some(Summary {count, min, max, mean, median})
+}
+
+fn histogram(values: &[usize]) -> Vec<Bucket> {
+    let (Some(min), Some(max)) = (values.iter().min(), values.iter().max()) else {
+        return Vec::new();
+    };
+    let range = max - min + 1;
+    let bucket_size = range.div_ceil(MAX_HISTOGRAM_BUCKETS);
+    let mut buckets: Vec<Bucket> = (0..range.div_ceil(bucket_size))
+        .map(|i| Bucket {
+            start: min + i * bucket_size,
+            end: (min + (i + 1) * bucket_size - 1).min(*max),
+            count: 0,
+        })
+        .collect();
+    for value in values {
+        buckets[(value - min) / bucket_size].count += 1;
+    }
+    buckets
+}
+
+fn render_histogram(buckets: &[Bucket]) -> Vec<String> {
+    let max_count = buckets.iter().map(|b| b.count).max().unwrap_or(0);
+    let labels: Vec<String> = buckets.iter()
+        .map(|b| if b.start == b.end {
+            b.start.to_string()
+        } else {
+            format!("{}-{}", b.start, b.end)
+        })
+        .collect();
+    let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+    buckets.iter().zip(labels)
+        .map(|(bucket, label)| bar_line(&label, bucket.count, max_count, label_width))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::{histogram, render_histogram, summarize, Bucket, Summary};
+
+    #[test]
+    fn test_summarize() {
+        assert_eq!(summarize(&[]), None);
+        assert_eq!(
+            summarize(&[4, 1, 3, 2]),
+            Some(Summary {count: 4, min: 1, max: 4, mean: 2.5, median: 2.5}),
+        );
+        assert_eq!(summarize(&[5, 1, 9]).map(|s| s.median), Some(5.0));
+    }
+
+    #[test]
+    fn test_histogram() {
+        let buckets = histogram(&[1, 1, 2, 25]);
+        assert_eq!(buckets.len(), 9);
+        assert_eq!(buckets[0], Bucket {start: 1, end: 3, count: 3});
+        assert_eq!(buckets[8], Bucket {start: 25, end: 25, count: 1});
+        assert_eq!(
+            render_histogram(&histogram(&[3, 3, 4])),
+            vec![
+                format!("3 | {} 2", "#".repeat(40)),
+                format!("4 | {} 1", "#".repeat(20)),
+            ],
+        );
+    }
+}
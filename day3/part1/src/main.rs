@@ -1,137 +1,159 @@
-use std::{env, fs::File, io::{self, BufRead}};
-
-const ADJECENT_CELLS_IDX_OFFSETS: [(i8, i8); 8] = [
-    (-1, -1),
-    (-1,  0),
-    (-1,  1),
-    ( 0, -1),
-    ( 0,  1),
-    ( 1, -1),
-    ( 1,  0),
-    ( 1,  1),
-];
-
-type CharsMatrix = Vec<Vec<char>>;
-
-struct CharWithInfo<'a> {
-    char_: &'a char,
-    is_digit: bool,
-    is_adjacent_to_symbol: bool,
-}
+use std::{env, fs::File, io::{self, BufRead, BufReader, Read}};
 
-type CharsWithInfoMatrix<'a> = Vec<Vec<CharWithInfo<'a>>>;
+use common::args::{check_flags, flag_value};
+use schematic::{
+    clusters::{find_clusters, largest_cluster}, graph::AdjacencyGraph, grid::Grid,
+    incremental::IncrementalSchematic, input::{self, RaggedPolicy, RAGGED_FLAGS}, numbers::Number,
+    render::{render, Viewport}, rules::{Rules, RULE_FLAGS}, stream::{solve_lines, Event},
+    tokens::TokenizedSchematic,
+};
 
-#[derive(Debug, PartialEq)]
-struct SchematicNum {
-    num: usize,
-    is_adjacent_to_symbol: bool,
-}
+type CharsMatrix = Grid<char>;
 
 fn main() -> Result<(), String> {
-    let file_path = env::args().nth(1).ok_or("No file provided!")?;
-    let file = File::open(file_path).map_err(|err| err.to_string())?;
+    let args: Vec<String> = env::args().skip(1).collect();
+    check_flags(&args, &[RULE_FLAGS, RAGGED_FLAGS, &["--format=", "--rows=", "--cols=", "--events"]])?;
+    let rules = Rules::from_args(&args)?;
+    let policy = RaggedPolicy::from_args(&args)?;
+    let mut positional_args = args.iter().filter(|arg| !arg.starts_with("--"));
+    let first_arg = positional_args.next().ok_or("No file provided!")?;
+    if first_arg == "graph" {
+        let file_path = positional_args.next().ok_or("No file provided!")?;
+        let graph = AdjacencyGraph::build(&read_chars_matrix(file_path, policy, &rules)?, &rules);
+        match flag_value(&args, "--format")? {
+            None | Some("dot") => println!("{}", graph.to_dot(&rules)),
+            Some("json") => println!("{}", graph.to_json()),
+            Some(format) => return Err(format!("Unknown graph format '{}'!", format)),
+        }
+        return Ok(());
+    }
+    if first_arg == "clusters" {
+        let file_path = positional_args.next().ok_or("No file provided!")?;
+        let schematic = TokenizedSchematic::tokenize(&read_chars_matrix(file_path, policy, &rules)?, &rules);
+        let clusters = find_clusters(&schematic, &rules);
+        for (cluster_idx, cluster) in clusters.iter().enumerate() {
+            println!(
+                "Cluster {}: {} members ({} numbers, {} symbols), sum {}, product {}, rows {}..{}, cols {}..{}",
+                cluster_idx, cluster.member_count(), cluster.numbers.len(), cluster.symbols.len(),
+                cluster.sum, cluster.product.map_or("overflow".to_string(), |product| product.to_string()),
+                cluster.bounding_box.row_start, cluster.bounding_box.row_end,
+                cluster.bounding_box.col_start, cluster.bounding_box.col_end,
+            );
+        }
+        if let Some(cluster_idx) = largest_cluster(&clusters) {
+            println!("Largest cluster: {} with {} members", cluster_idx, clusters[cluster_idx].member_count());
+        }
+        return Ok(());
+    }
+    if first_arg == "render" {
+        let file_path = positional_args.next().ok_or("No file provided!")?;
+        let viewport = Viewport::parse(flag_value(&args, "--rows")?, flag_value(&args, "--cols")?)?;
+        let chars_mat = read_chars_matrix(file_path, policy, &rules)?;
+        let schematic = TokenizedSchematic::tokenize(&chars_mat, &rules);
+        println!("{}", render(&chars_mat, &schematic, &rules, &viewport));
+        return Ok(());
+    }
+
+    if first_arg == "stream" {
+        // Reads from stdin unless a file is given, keeping only three rows in memory.
+        let reader: Box<dyn Read> = match positional_args.next() {
+            Some(file_path) if file_path != "-" => {
+                Box::new(File::open(file_path).map_err(|err| err.to_string())?)
+            }
+            _ => Box::new(io::stdin()),
+        };
+        let print_events = args.iter().any(|arg| arg == "--events");
+        let lines = BufReader::new(reader).lines().map(|line| line.map_err(|err| err.to_string()));
+        let totals = solve_lines(lines, policy, &rules, |event| {
+            if !print_events {
+                return;
+            }
+            match event {
+                Event::PartNumber(number) => println!(
+                    "part number {} at ({}, {})", number.value, number.row, number.col_start,
+                ),
+                Event::GearRatio {row, col, ratio} => println!(
+                    "gear ratio {} at ({}, {})", ratio, row, col,
+                ),
+            }
+        })?;
+        println!("Sum of part numbers: {}", totals.part_number_sum);
+        println!("Sum of gear ratios: {}", totals.gear_ratio_sum);
+        return Ok(());
+    }
+
+    if first_arg == "edit" {
+        let file_path = positional_args.next().ok_or("No file provided!")?;
+        let mut schematic = IncrementalSchematic::new(read_chars_matrix(file_path, policy, &rules)?, rules);
+        println!("{} {}", schematic.part_number_sum(), schematic.gear_ratio_sum());
+        // Each stdin line is an edit '<row> <col> <char>'.
+        for line in io::stdin().lines() {
+            let line = line.map_err(|err| err.to_string())?;
+            let (row, col, c) = parse_edit(&line)
+                .ok_or(format!("Edit '{}' must look like '<row> <col> <char>'!", line))?;
+            schematic.set_cell(row, col, c)?;
+            println!("{} {}", schematic.part_number_sum(), schematic.gear_ratio_sum());
+        }
+        return Ok(());
+    }
+
+    let file = File::open(first_arg).map_err(|err| err.to_string())?;
     let file_lines = io::BufReader::new(file).lines()
-        .map(|l| l.unwrap());
-    let sum: usize = lines_to_schematic_numbers(file_lines).iter()
-        .filter(|num| num.is_adjacent_to_symbol)
-        .map(|num| num.num)
+        .collect::<Result<Vec<String>, _>>().map_err(|err| err.to_string())?;
+    let sum: usize = lines_to_schematic_numbers(file_lines.into_iter(), policy, &rules)?.iter()
+        .filter(|(_, is_part_number)| *is_part_number)
+        .map(|(number, _)| number.value)
         .sum();
     println!("Sum of part numbers: {}", sum);
 
     Ok(())
 }
 
-fn lines_to_schematic_numbers<'a>(
+fn lines_to_schematic_numbers(
     lines: impl Iterator<Item = String>,
-) -> Vec<SchematicNum> {
-    let chars_mat = lines_to_chars_matrix(lines);
-    let chars_info_mat = add_info_to_chars_matrix(&chars_mat);
-    chars_with_info_matrix_to_schematic_numbers(chars_info_mat)
+    policy: RaggedPolicy,
+    rules: &Rules,
+) -> Result<Vec<(Number, bool)>, String> {
+    let chars_mat = lines_to_chars_matrix(lines, policy, rules)?;
+    let schematic = TokenizedSchematic::tokenize(&chars_mat, rules);
+    Ok(schematic.numbers()
+        .map(|(token_idx, number)| (number.clone(), schematic.is_part_number(token_idx, rules)))
+        .collect())
 }
 
-fn add_info_to_chars_matrix<'a>(mat: &'a CharsMatrix) -> CharsWithInfoMatrix<'a> {
-    let mut info_mat = Vec::new();
-    for (i, row) in mat.iter().enumerate() {
-        let mut info_row = Vec::new();
-        for (j, c) in row.iter().enumerate() {
-            info_row.push(CharWithInfo {
-                char_: c,
-                is_digit: '0' <= *c && *c <= '9',
-                is_adjacent_to_symbol:
-                    ADJECENT_CELLS_IDX_OFFSETS.iter().any(|(i_offset, j_offset)| {
-                        mat.get(((i as i64) + (*i_offset as i64)) as usize).map(|r| {
-                            r.get(((j as i64) + (*j_offset as i64)) as usize)
-                                .map(|c| {
-                                    (*c < '0' || '9' < *c) && *c != '.'
-                                }).unwrap_or(false)
-                        }).unwrap_or(false)
-                    }),
-            });
-        }
-        info_mat.push(info_row);
+fn parse_edit(line: &str) -> Option<(usize, usize, char)> {
+    let mut parts = line.split(' ');
+    let row = parts.next()?.parse::<usize>().ok()?;
+    let col = parts.next()?.parse::<usize>().ok()?;
+    let mut chars = parts.next()?.chars();
+    let c = chars.next()?;
+    if chars.next().is_some() || parts.next().is_some() {
+        return None;
     }
-    info_mat
+    Some((row, col, c))
 }
 
-fn chars_with_info_matrix_to_schematic_numbers(
-    mat: CharsWithInfoMatrix,
-) -> Vec<SchematicNum> {
-    let mut part_numbers = Vec::<SchematicNum>::new();
-    let mut num_digits = Vec::<&char>::new();
-    let mut is_in_num = false;
-    let mut is_adjacent_to_symbol = false;
-    for row in mat {
-        for cell in row {
-            if is_in_num {
-                if cell.is_digit {
-                    num_digits.push(cell.char_);
-                    is_adjacent_to_symbol = is_adjacent_to_symbol || cell.is_adjacent_to_symbol;
-                } else {
-                    let mut num = 0;
-                    for dig in &num_digits {
-                        num = num*10 + (**dig as usize - '0' as usize);
-                    }
-                    part_numbers.push(SchematicNum {
-                        num,
-                        is_adjacent_to_symbol,
-                    });
-                    num_digits.clear();
-                    is_in_num = false;
-                    is_adjacent_to_symbol = false;
-                }
-            } else {
-                if cell.is_digit {
-                    num_digits.push(cell.char_);
-                    is_in_num = true;
-                    is_adjacent_to_symbol = cell.is_adjacent_to_symbol;
-                }
-            }
-        }
-    }
-    if is_in_num {
-        let mut num = 0;
-        for dig in num_digits {
-            num = num*10 + (*dig as usize - '0' as usize);
-        }
-        part_numbers.push(SchematicNum {
-            num,
-            is_adjacent_to_symbol,
-        });
-    }
-    part_numbers
+fn read_chars_matrix(file_path: &str, policy: RaggedPolicy, rules: &Rules) -> Result<CharsMatrix, String> {
+    let file = File::open(file_path).map_err(|err| err.to_string())?;
+    let file_lines = io::BufReader::new(file).lines()
+        .collect::<Result<Vec<String>, _>>().map_err(|err| err.to_string())?;
+    lines_to_chars_matrix(file_lines.into_iter(), policy, rules)
 }
 
-fn lines_to_chars_matrix<'a>(lines: impl Iterator<Item = String>) -> CharsMatrix {
-    let mut mat = Vec::new();
-    for line in lines {
-        mat.push(line.chars().collect());
-    }
-    return mat
+fn lines_to_chars_matrix(
+    lines: impl Iterator<Item = String>,
+    policy: RaggedPolicy,
+    rules: &Rules,
+) -> Result<CharsMatrix, String> {
+    let lines: Vec<String> = lines.collect();
+    input::parse_lines(lines.iter().map(|line| line.as_str()), policy, rules.blank())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{lines_to_schematic_numbers, SchematicNum};
+    use schematic::{input::RaggedPolicy, numbers::Number, rules::Rules};
+
+    use crate::lines_to_schematic_numbers;
 
     #[test]
     fn test_lines_to_schematic_numbers() {
@@ -147,19 +169,62 @@ mod tests {
                 "......755.",
                 "...$.*....",
                 ".664.598..",
-            ].iter().map(|l| l.to_string())),
-            vec![
-                SchematicNum {num: 467, is_adjacent_to_symbol: true},
-                SchematicNum {num: 114, is_adjacent_to_symbol: false},
-                SchematicNum {num: 35, is_adjacent_to_symbol: true},
-                SchematicNum {num: 633, is_adjacent_to_symbol: true},
-                SchematicNum {num: 617, is_adjacent_to_symbol: true},
-                SchematicNum {num: 58, is_adjacent_to_symbol: false},
-                SchematicNum {num: 592, is_adjacent_to_symbol: true},
-                SchematicNum {num: 755, is_adjacent_to_symbol: true},
-                SchematicNum {num: 664, is_adjacent_to_symbol: true},
-                SchematicNum {num: 598, is_adjacent_to_symbol: true},
-            ],
+            ].iter().map(|l| l.to_string()), RaggedPolicy::Reject, &Rules::default()),
+            Ok(vec![
+                (Number {value: 467, row: 0, col_start: 0, col_end: 3}, true),
+                (Number {value: 114, row: 0, col_start: 5, col_end: 8}, false),
+                (Number {value: 35, row: 2, col_start: 2, col_end: 4}, true),
+                (Number {value: 633, row: 2, col_start: 6, col_end: 9}, true),
+                (Number {value: 617, row: 4, col_start: 0, col_end: 3}, true),
+                (Number {value: 58, row: 5, col_start: 7, col_end: 9}, false),
+                (Number {value: 592, row: 6, col_start: 2, col_end: 5}, true),
+                (Number {value: 755, row: 7, col_start: 6, col_end: 9}, true),
+                (Number {value: 664, row: 9, col_start: 1, col_end: 4}, true),
+                (Number {value: 598, row: 9, col_start: 5, col_end: 8}, true),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_lines_to_schematic_numbers_on_row_edges() {
+        let nums = lines_to_schematic_numbers([
+            "...1",
+            "2#..",
+            "...3",
+            "4...",
+        ].iter().map(|l| l.to_string()), RaggedPolicy::Reject, &Rules::default()).unwrap();
+        assert_eq!(
+            nums.iter().map(|(number, is_part_number)| (number.value, *is_part_number)).collect::<Vec<_>>(),
+            vec![(1, false), (2, true), (3, false), (4, false)],
+        );
+    }
+
+    #[test]
+    fn test_lines_to_schematic_numbers_with_custom_symbols() {
+        let rules = Rules {symbols: Some(vec!['#']), ..Rules::default()};
+        let nums = lines_to_schematic_numbers([
+            "1..2",
+            "#..$",
+        ].iter().map(|l| l.to_string()), RaggedPolicy::Reject, &rules).unwrap();
+        assert_eq!(
+            nums.iter().map(|(number, is_part_number)| (number.value, *is_part_number)).collect::<Vec<_>>(),
+            vec![(1, true), (2, false)],
+        );
+    }
+
+    #[test]
+    fn test_lines_to_schematic_numbers_with_ragged_rows() {
+        let lines = ["12.\r", "*", "..3"];
+        assert_eq!(
+            lines_to_schematic_numbers(lines.iter().map(|l| l.to_string()), RaggedPolicy::Reject, &Rules::default()),
+            Err("Row 2 has length 1, expected 3!".to_string()),
+        );
+        let nums = lines_to_schematic_numbers(
+            lines.iter().map(|l| l.to_string()), RaggedPolicy::Pad, &Rules::default(),
+        ).unwrap();
+        assert_eq!(
+            nums.iter().map(|(number, is_part_number)| (number.value, *is_part_number)).collect::<Vec<_>>(),
+            vec![(12, true), (3, false)],
         );
     }
 }
@@ -1,160 +1,41 @@
-use std::{fs::File, io::Read};
+use std::{env, fs::File, io::Read};
 
-#[derive(Debug, PartialEq)]
-enum Token {
-    Num(usize),
-    Gear,
-    Unknown,
-}
-
-#[derive(Debug, PartialEq)]
-struct TokenMatrix {
-    idx_matrix: Vec<Vec<usize>>,
-    tokens: Vec<Token>,
-}
+use common::args::check_flags;
+use schematic::{
+    input::{self, RaggedPolicy, RAGGED_FLAGS}, rules::{Rules, RULE_FLAGS}, tokens::TokenizedSchematic,
+};
 
 fn main() -> Result<(), String> {
-    let mut file = File::open("../input.txt").map_err(|err| err.to_string())?;
+    let args: Vec<String> = env::args().skip(1).collect();
+    check_flags(&args, &[RULE_FLAGS, RAGGED_FLAGS])?;
+    let rules = Rules::from_args(&args)?;
+    let policy = RaggedPolicy::from_args(&args)?;
+    let file_path = args.iter().find(|arg| !arg.starts_with("--"))
+        .map_or("../input.txt", |arg| arg.as_str());
+    let mut file = File::open(file_path).map_err(|err| err.to_string())?;
     let mut input = String::new();
-    let _ = file.read_to_string(&mut input);
-    println!("Sum of gear ratios: {}", sum_gear_ratios(&input));
+    file.read_to_string(&mut input).map_err(|err| err.to_string())?;
+    println!("Sum of gear ratios: {}", sum_gear_ratios(&input, policy, &rules)?);
 
     Ok(())
 }
 
-fn sum_gear_ratios(input: &str) -> usize {
-    let token_matrix = tokenize(input);
-    let mut sum = 0;
-    for row_idx in 0..token_matrix.idx_matrix.len() {
-        for col_idx in 0..token_matrix.idx_matrix[row_idx].len() {
-            gear_ratio_at_cell(&token_matrix, row_idx, col_idx).map(|x| sum += x);
-        }
-    }
-    sum
+fn sum_gear_ratios(input: &str, policy: RaggedPolicy, rules: &Rules) -> Result<usize, String> {
+    Ok(tokenize(input, policy, rules)?.gear_ratio_sum(rules))
 }
 
-fn tokenize(input: &str) -> TokenMatrix {
-    let mut idx_matrix = Vec::<Vec<usize>>::new();
-    let mut idx_matrix_row = Vec::<usize>::new();
-    let mut tokens = Vec::<Token>::new();
-    let mut token_idx = 0;
-
-    let mut is_in_num = false;
-    let mut num_chars = Vec::<char>::new();
-    let mut num_len = 0;
-
-    for char in input.chars() {
-        if is_in_num {
-            if '0' <= char && char <= '9' {
-                num_chars.push(char);
-                num_len += 1;
-                continue;
-            }
-
-            let mut num = 0;
-            for num_char in &num_chars {
-                num = 10*num + (*num_char as usize - '0' as usize);
-            }
-            let token = Token::Num(num);
-            for _ in 0..num_len {
-                idx_matrix_row.push(token_idx);
-            }
-            tokens.push(token);
-            token_idx += 1;
-            is_in_num = false;
-            num_chars.clear();
-            num_len = 0;
-        }
-
-        if '0' <= char && char <= '9' {
-            is_in_num = true;
-            num_chars.push(char);
-            num_len = 1;
-            continue;
-        }
-
-        if char == '\n' {
-            idx_matrix.push(idx_matrix_row.clone());
-            idx_matrix_row.clear();
-            continue;
-        }
-
-        if char == '*' {
-            let token = Token::Gear;
-            idx_matrix_row.push(token_idx);
-            tokens.push(token);
-            token_idx += 1;
-            continue;
-        }
-
-        let token = Token::Unknown;
-        idx_matrix_row.push(token_idx);
-        tokens.push(token);
-        token_idx += 1;
-    }
-
-    if is_in_num {
-        let mut num = 0;
-        for num_char in &num_chars {
-            num = 10*num + (*num_char as usize - '0' as usize);
-        }
-        let token = Token::Num(num);
-        for _ in 0..num_len {
-            idx_matrix_row.push(token_idx);
-        }
-        tokens.push(token);
-    }
-
-    if idx_matrix.len() > 0 {
-        idx_matrix.push(idx_matrix_row.clone());
-        idx_matrix_row.clear();
-    }
-    
-    TokenMatrix {idx_matrix, tokens}
-}
-
-fn gear_ratio_at_cell(token_matrix: &TokenMatrix, row: usize, col: usize) -> Option<usize> {
-    let token_idx = token_matrix.idx_matrix[row][col];
-    let _ = match token_matrix.tokens[token_idx] {
-        Token::Gear => Some(()),
-        _ => None
-    }?;
-    let mut adj_num_count = 0;
-    let mut gear_ratio = 1;
-    let mut encountered_adj_num_idxs = Vec::<usize>::new();
-    for (adj_row, adj_col) in [
-        (row - 1, col - 1),
-        (row - 1, col    ),
-        (row - 1, col + 1),
-        (row    , col - 1),
-        (row    , col + 1),
-        (row + 1, col - 1),
-        (row + 1, col    ),
-        (row + 1, col + 1),
-    ] {
-        let opt_adj_token_idx = token_matrix.idx_matrix.get(adj_row).map(|r| r.get(adj_col)).flatten();
-        if let Some(adj_token_idx) = opt_adj_token_idx {
-            if let Token::Num(adj_num) = token_matrix.tokens[*adj_token_idx] {
-                if encountered_adj_num_idxs.contains(adj_token_idx) {
-                    continue;
-                }
-                gear_ratio *= adj_num;
-                adj_num_count += 1;
-                encountered_adj_num_idxs.push(*adj_token_idx);
-            }
-        }
-    }
-
-    if adj_num_count != 2 {
-        return None;
-    }
-
-    return Some(gear_ratio);
+fn tokenize(input: &str, policy: RaggedPolicy, rules: &Rules) -> Result<TokenizedSchematic, String> {
+    let chars_matrix = input::parse_text(input, policy, rules.blank())?;
+    Ok(TokenizedSchematic::tokenize(&chars_matrix, rules))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{TokenMatrix, Token, tokenize, sum_gear_ratios};
+    use schematic::{
+        input::RaggedPolicy, numbers::Number, rules::{AdjacentCount, Combine, Rules}, tokens::Token,
+    };
+
+    use crate::{tokenize, sum_gear_ratios};
 
     #[test]
     fn test_lines_to_schematic_numbers() {
@@ -163,29 +44,26 @@ mod tests {
             "...*.",
             "..35.",
         ].join("\n");
+        let schematic = tokenize(&input, RaggedPolicy::Reject, &Rules::default()).unwrap();
+        assert_eq!(schematic.idx_grid().row(0), Some(&[0, 0, 0, 1, 2][..]));
+        assert_eq!(schematic.idx_grid().row(1), Some(&[3, 4, 5, 6, 7][..]));
+        assert_eq!(schematic.idx_grid().row(2), Some(&[8, 9, 10, 10, 11][..]));
         assert_eq!(
-            tokenize(&input),
-            TokenMatrix {
-                idx_matrix: vec![
-                    vec![0, 0, 0, 1, 2],
-                    vec![3, 4, 5, 6, 7],
-                    vec![8, 9, 10, 10, 11],
-                ],
-                tokens: vec![
-                    Token::Num(467),
-                    Token::Unknown,
-                    Token::Unknown,
-                    Token::Unknown,
-                    Token::Unknown,
-                    Token::Unknown,
-                    Token::Gear,
-                    Token::Unknown,
-                    Token::Unknown,
-                    Token::Unknown,
-                    Token::Num(35),
-                    Token::Unknown,
-                ],
-            },
+            schematic.tokens(),
+            &[
+                Token::Number(Number {value: 467, row: 0, col_start: 0, col_end: 3}),
+                Token::Blank,
+                Token::Blank,
+                Token::Blank,
+                Token::Blank,
+                Token::Blank,
+                Token::Symbol('*'),
+                Token::Blank,
+                Token::Blank,
+                Token::Blank,
+                Token::Number(Number {value: 35, row: 2, col_start: 2, col_end: 4}),
+                Token::Blank,
+            ][..],
         );
     }
 
@@ -204,8 +82,62 @@ mod tests {
             ".664.598..",
         ].join("\n");
         assert_eq!(
-            sum_gear_ratios(&input),
-            467835,
+            sum_gear_ratios(&input, RaggedPolicy::Reject, &Rules::default()),
+            Ok(467835),
+        );
+    }
+
+    #[test]
+    fn test_sum_gear_ratios_gear_on_edge() {
+        assert_eq!(sum_gear_ratios("*12\n3..\n...\n", RaggedPolicy::Reject, &Rules::default()), Ok(36));
+        assert_eq!(sum_gear_ratios("...\n..4\n.5*", RaggedPolicy::Reject, &Rules::default()), Ok(20));
+    }
+
+    #[test]
+    fn test_tokenize_numbers_on_row_edges() {
+        let schematic = tokenize("..12\n34..", RaggedPolicy::Reject, &Rules::default()).unwrap();
+        assert_eq!(schematic.tokens()[2], Token::Number(Number {value: 12, row: 0, col_start: 2, col_end: 4}));
+        assert_eq!(schematic.tokens()[3], Token::Number(Number {value: 34, row: 1, col_start: 0, col_end: 2}));
+        assert_eq!(schematic.idx_grid().row(1), Some(&[3, 3, 4, 5][..]));
+    }
+
+    #[test]
+    fn test_sum_gear_ratios_with_custom_rules() {
+        let input = "1.2\n.#.\n3*.";
+        let rules = Rules {
+            gears: vec!['#'],
+            gear_adjacent_count: AdjacentCount::AtLeast(3),
+            gear_combine: Combine::Sum,
+            ..Rules::default()
+        };
+        assert_eq!(sum_gear_ratios(input, RaggedPolicy::Reject, &rules), Ok(6));
+        assert_eq!(sum_gear_ratios(input, RaggedPolicy::Reject, &Rules::default()), Ok(0));
+        let rules = Rules {gear_adjacent_count: AdjacentCount::Exactly(1), ..Rules::default()};
+        assert_eq!(sum_gear_ratios(input, RaggedPolicy::Reject, &rules), Ok(3));
+    }
+
+    #[test]
+    fn test_tokenize_single_row() {
+        let schematic = tokenize("12*3", RaggedPolicy::Reject, &Rules::default()).unwrap();
+        assert_eq!(
+            schematic.tokens(),
+            &[
+                Token::Number(Number {value: 12, row: 0, col_start: 0, col_end: 2}),
+                Token::Symbol('*'),
+                Token::Number(Number {value: 3, row: 0, col_start: 3, col_end: 4}),
+            ][..],
+        );
+        assert_eq!(sum_gear_ratios("12*3", RaggedPolicy::Reject, &Rules::default()), Ok(36));
+    }
+
+    #[test]
+    fn test_sum_gear_ratios_with_crlf_and_ragged_rows() {
+        let input = "12.\r\n.*\r\n3..\r\n";
+        assert_eq!(
+            sum_gear_ratios(input, RaggedPolicy::Reject, &Rules::default()),
+            Err("Row 2 has length 2, expected 3!".to_string()),
         );
+        assert_eq!(sum_gear_ratios(input, RaggedPolicy::Pad, &Rules::default()), Ok(36));
+        assert_eq!(sum_gear_ratios(input, RaggedPolicy::MissingAsBlank, &Rules::default()), Ok(36));
     }
 }
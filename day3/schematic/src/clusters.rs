@@ -0,0 +1,187 @@
+use crate::{rules::Rules, tokens::{Token, TokenizedSchematic}};
+
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> UnionFind {
+        UnionFind {parents: (0..len).collect(), sizes: vec![1; len]}
+    }
+
+    pub fn find(&mut self, idx: usize) -> usize {
+        let mut root = idx;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // Path compression.
+        let mut idx = idx;
+        while self.parents[idx] != root {
+            let next = self.parents[idx];
+            self.parents[idx] = root;
+            idx = next;
+        }
+        root
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+    }
+}
+
+// Inclusive start, exclusive end like `Number::col_end`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundingBox {
+    pub row_start: usize,
+    pub row_end: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+}
+
+impl BoundingBox {
+    fn extend(&mut self, row: usize, col_start: usize, col_end: usize) {
+        self.row_start = self.row_start.min(row);
+        self.row_end = self.row_end.max(row + 1);
+        self.col_start = self.col_start.min(col_start);
+        self.col_end = self.col_end.max(col_end);
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cluster {
+    // Token indices of the numbers and the symbols (including gears).
+    pub numbers: Vec<usize>,
+    pub symbols: Vec<usize>,
+    pub sum: usize,
+    // `None` if the product overflows.
+    pub product: Option<usize>,
+    pub bounding_box: BoundingBox,
+}
+
+impl Cluster {
+    pub fn member_count(&self) -> usize {
+        self.numbers.len() + self.symbols.len()
+    }
+}
+
+// Numbers and symbols are connected whenever they touch. Clusters are ordered by
+// their first token in reading order.
+pub fn find_clusters(schematic: &TokenizedSchematic, rules: &Rules) -> Vec<Cluster> {
+    let tokens = schematic.tokens();
+    let is_member = |token_idx: usize| match &tokens[token_idx] {
+        Token::Number(_) => true,
+        Token::Symbol(c) => rules.is_symbol(*c) || rules.is_gear(*c),
+        Token::Blank => false,
+    };
+    let mut union_find = UnionFind::new(tokens.len());
+    for (token_idx, token) in tokens.iter().enumerate() {
+        if !matches!(token, Token::Number(_)) {
+            continue;
+        }
+        for adj_idx in schematic.adjacent_tokens(token_idx) {
+            if is_member(adj_idx) {
+                union_find.union(token_idx, adj_idx);
+            }
+        }
+    }
+
+    let mut clusters = Vec::<Cluster>::new();
+    let mut root_clusters = vec![None; tokens.len()];
+    for token_idx in (0..tokens.len()).filter(|token_idx| is_member(*token_idx)) {
+        let cells = schematic.cells(token_idx);
+        let (Some((row, col_start)), Some((_, last_col))) = (cells.first(), cells.last()) else {
+            continue;
+        };
+        let (row, col_start, col_end) = (*row, *col_start, last_col + 1);
+        let root = union_find.find(token_idx);
+        let cluster_idx = *root_clusters[root].get_or_insert_with(|| {
+            clusters.push(Cluster {
+                numbers: Vec::new(),
+                symbols: Vec::new(),
+                sum: 0,
+                product: Some(1),
+                bounding_box: BoundingBox {row_start: row, row_end: row + 1, col_start, col_end},
+            });
+            clusters.len() - 1
+        });
+        let cluster = &mut clusters[cluster_idx];
+        cluster.bounding_box.extend(row, col_start, col_end);
+        match &tokens[token_idx] {
+            Token::Number(number) => {
+                cluster.numbers.push(token_idx);
+                cluster.sum += number.value;
+                cluster.product = cluster.product.and_then(|product| product.checked_mul(number.value));
+            }
+            _ => cluster.symbols.push(token_idx),
+        }
+    }
+    clusters
+}
+
+// Ties go to the earlier cluster.
+pub fn largest_cluster(clusters: &[Cluster]) -> Option<usize> {
+    (0..clusters.len()).rev().max_by_key(|cluster_idx| clusters[*cluster_idx].member_count())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        clusters::{find_clusters, largest_cluster, BoundingBox, UnionFind},
+        grid::Grid, rules::Rules, tokens::TokenizedSchematic,
+    };
+
+    #[test]
+    fn test_union_find() {
+        let mut union_find = UnionFind::new(5);
+        union_find.union(0, 3);
+        union_find.union(3, 4);
+        assert_eq!(union_find.find(4), union_find.find(0));
+        assert_ne!(union_find.find(1), union_find.find(0));
+        union_find.union(1, 2);
+        union_find.union(2, 4);
+        assert!((0..5).all(|idx| union_find.find(idx) == union_find.find(0)));
+    }
+
+    #[test]
+    fn test_find_clusters() {
+        let grid = Grid::parse([
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ].join("\n").as_str(), |c| c).unwrap();
+        let rules = Rules::default();
+        let clusters = find_clusters(&TokenizedSchematic::tokenize(&grid, &rules), &rules);
+        let summaries: Vec<(usize, usize, Option<usize>)> = clusters.iter()
+            .map(|cluster| (cluster.member_count(), cluster.sum, cluster.product))
+            .collect();
+        assert_eq!(summaries, vec![
+            (3, 502, Some(16345)),
+            (1, 114, Some(114)),
+            (2, 633, Some(633)),
+            (2, 617, Some(617)),
+            (2, 592, Some(592)),
+            (1, 58, Some(58)),
+            (3, 1353, Some(451490)),
+            (2, 664, Some(664)),
+        ]);
+        assert_eq!(largest_cluster(&clusters), Some(0));
+        assert_eq!(clusters[0].bounding_box, BoundingBox {row_start: 0, row_end: 3, col_start: 0, col_end: 4});
+        assert_eq!(clusters[6].bounding_box, BoundingBox {row_start: 7, row_end: 10, col_start: 5, col_end: 9});
+    }
+}
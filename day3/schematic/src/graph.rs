@@ -0,0 +1,238 @@
+use crate::{grid::Grid, numbers::Number, rules::Rules, tokens::{Token, TokenizedSchematic}};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbol {
+    pub char_: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+// Bipartite graph between numbers and the symbols (including gears) they touch.
+#[derive(Debug, PartialEq)]
+pub struct AdjacencyGraph {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    number_symbols: Vec<Vec<usize>>,
+    symbol_numbers: Vec<Vec<usize>>,
+}
+
+impl AdjacencyGraph {
+    pub fn build(grid: &Grid<char>, rules: &Rules) -> AdjacencyGraph {
+        AdjacencyGraph::from_tokens(&TokenizedSchematic::tokenize(grid, rules), rules)
+    }
+
+    // Numbers and symbols keep the reading order of their tokens.
+    pub fn from_tokens(schematic: &TokenizedSchematic, rules: &Rules) -> AdjacencyGraph {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        let mut token_num_idxs = vec![None; schematic.tokens().len()];
+        let mut symbol_token_idxs = Vec::new();
+        for (token_idx, token) in schematic.tokens().iter().enumerate() {
+            match token {
+                Token::Number(number) => {
+                    token_num_idxs[token_idx] = Some(numbers.len());
+                    numbers.push(number.clone());
+                }
+                Token::Symbol(c) if rules.is_symbol(*c) || rules.is_gear(*c) => {
+                    if let Some((row, col)) = schematic.first_cell(token_idx) {
+                        symbols.push(Symbol {char_: *c, row, col});
+                        symbol_token_idxs.push(token_idx);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut number_symbols = vec![Vec::new(); numbers.len()];
+        let mut symbol_numbers = vec![Vec::new(); symbols.len()];
+        for (sym_idx, token_idx) in symbol_token_idxs.into_iter().enumerate() {
+            for adj_idx in schematic.adjacent_tokens(token_idx) {
+                if let Some(num_idx) = token_num_idxs[adj_idx] {
+                    symbol_numbers[sym_idx].push(num_idx);
+                    number_symbols[num_idx].push(sym_idx);
+                }
+            }
+        }
+        AdjacencyGraph {numbers, symbols, number_symbols, symbol_numbers}
+    }
+
+    pub fn symbols_adjacent_to_number(&self, num_idx: usize) -> &[usize] {
+        self.number_symbols.get(num_idx).map_or(&[], |idxs| idxs.as_slice())
+    }
+
+    pub fn numbers_adjacent_to_symbol(&self, sym_idx: usize) -> &[usize] {
+        self.symbol_numbers.get(sym_idx).map_or(&[], |idxs| idxs.as_slice())
+    }
+
+    pub fn number_at(&self, row: usize, col: usize) -> Option<usize> {
+        self.numbers.iter().position(|number| number.contains(row, col))
+    }
+
+    pub fn symbol_at(&self, row: usize, col: usize) -> Option<usize> {
+        self.symbols.iter().position(|symbol| symbol.row == row && symbol.col == col)
+    }
+
+    pub fn is_part_number(&self, num_idx: usize, rules: &Rules) -> bool {
+        self.symbols_adjacent_to_number(num_idx).iter()
+            .any(|sym_idx| rules.is_symbol(self.symbols[*sym_idx].char_))
+    }
+
+    pub fn gear_ratio(&self, sym_idx: usize, rules: &Rules) -> Option<usize> {
+        if !rules.is_gear(self.symbols.get(sym_idx)?.char_) {
+            return None;
+        }
+        let adj_nums: Vec<usize> = self.numbers_adjacent_to_symbol(sym_idx).iter()
+            .map(|num_idx| self.numbers[*num_idx].value)
+            .collect();
+        if !rules.accepts_gear_adjacent_count(adj_nums.len()) {
+            return None;
+        }
+        Some(rules.combine(&adj_nums))
+    }
+
+    pub fn part_number_sum(&self, rules: &Rules) -> usize {
+        (0..self.numbers.len())
+            .filter(|num_idx| self.is_part_number(*num_idx, rules))
+            .map(|num_idx| self.numbers[num_idx].value)
+            .sum()
+    }
+
+    pub fn gear_ratio_sum(&self, rules: &Rules) -> usize {
+        (0..self.symbols.len())
+            .filter_map(|sym_idx| self.gear_ratio(sym_idx, rules))
+            .sum()
+    }
+
+    pub fn to_dot(&self, rules: &Rules) -> String {
+        let mut lines = vec!["graph schematic {".to_string()];
+        for (num_idx, number) in self.numbers.iter().enumerate() {
+            lines.push(format!(
+                "    n{} [label=\"{}\\n({}, {}-{})\", shape=box, color={}];",
+                num_idx, number.value, number.row, number.col_start, number.col_end - 1,
+                if self.is_part_number(num_idx, rules) {"green"} else {"red"},
+            ));
+        }
+        for (sym_idx, symbol) in self.symbols.iter().enumerate() {
+            lines.push(format!(
+                "    s{} [label=\"{}\\n({}, {})\", shape=diamond];",
+                sym_idx, escape(symbol.char_), symbol.row, symbol.col,
+            ));
+        }
+        for (num_idx, sym_idxs) in self.number_symbols.iter().enumerate() {
+            for sym_idx in sym_idxs {
+                lines.push(format!("    n{} -- s{};", num_idx, sym_idx));
+            }
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        let numbers: Vec<String> = self.numbers.iter().enumerate()
+            .map(|(num_idx, number)| format!(
+                "{{\"id\":{},\"value\":{},\"row\":{},\"col_start\":{},\"col_end\":{}}}",
+                num_idx, number.value, number.row, number.col_start, number.col_end,
+            ))
+            .collect();
+        let symbols: Vec<String> = self.symbols.iter().enumerate()
+            .map(|(sym_idx, symbol)| format!(
+                "{{\"id\":{},\"char\":\"{}\",\"row\":{},\"col\":{}}}",
+                sym_idx, escape(symbol.char_), symbol.row, symbol.col,
+            ))
+            .collect();
+        let edges: Vec<String> = self.number_symbols.iter().enumerate()
+            .flat_map(|(num_idx, sym_idxs)| sym_idxs.iter().map(move |sym_idx| {
+                format!("{{\"number\":{},\"symbol\":{}}}", num_idx, sym_idx)
+            }))
+            .collect();
+        format!(
+            "{{\"numbers\":[{}],\"symbols\":[{}],\"edges\":[{}]}}",
+            numbers.join(","), symbols.join(","), edges.join(","),
+        )
+    }
+}
+
+// Both DOT and JSON strings use backslash escapes for quotes and backslashes.
+// JSON does not allow raw control characters inside strings.
+fn escape(c: char) -> String {
+    match c {
+        '"' => "\\\"".to_string(),
+        '\\' => "\\\\".to_string(),
+        _ if c < '\u{20}' => format!("\\u{:04x}", c as u32),
+        _ => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{graph::{escape, AdjacencyGraph}, grid::Grid, rules::Rules};
+
+    fn example_graph() -> AdjacencyGraph {
+        let grid = Grid::parse([
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ].join("\n").as_str(), |c| c).unwrap();
+        AdjacencyGraph::build(&grid, &Rules::default())
+    }
+
+    #[test]
+    fn test_adjacency_queries() {
+        let graph = example_graph();
+        let rules = Rules::default();
+        let gear_idx = graph.symbol_at(1, 3).unwrap();
+        let adj_values: Vec<usize> = graph.numbers_adjacent_to_symbol(gear_idx).iter()
+            .map(|num_idx| graph.numbers[*num_idx].value)
+            .collect();
+        assert_eq!(adj_values, vec![467, 35]);
+        let num_idx = graph.number_at(9, 2).unwrap();
+        assert_eq!(graph.numbers[num_idx].value, 664);
+        assert_eq!(graph.symbols_adjacent_to_number(num_idx), &[graph.symbol_at(8, 3).unwrap()]);
+        assert!(!graph.is_part_number(graph.number_at(0, 5).unwrap(), &rules));
+        assert_eq!(graph.gear_ratio(gear_idx, &rules), Some(16345));
+        assert_eq!(graph.gear_ratio(graph.symbol_at(4, 3).unwrap(), &rules), None);
+        assert_eq!(graph.part_number_sum(&rules), 4361);
+        assert_eq!(graph.gear_ratio_sum(&rules), 467835);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape('#'), "#");
+        assert_eq!(escape('"'), "\\\"");
+        assert_eq!(escape('\u{1}'), "\\u0001");
+        assert_eq!(escape('\u{1f}'), "\\u001f");
+    }
+
+    #[test]
+    fn test_exports() {
+        let grid = Grid::parse("12\n\"3", |c| c).unwrap();
+        let graph = AdjacencyGraph::build(&grid, &Rules::default());
+        assert_eq!(
+            graph.to_json(),
+            concat!(
+                "{\"numbers\":[{\"id\":0,\"value\":12,\"row\":0,\"col_start\":0,\"col_end\":2},",
+                "{\"id\":1,\"value\":3,\"row\":1,\"col_start\":1,\"col_end\":2}],",
+                "\"symbols\":[{\"id\":0,\"char\":\"\\\"\",\"row\":1,\"col\":0}],",
+                "\"edges\":[{\"number\":0,\"symbol\":0},{\"number\":1,\"symbol\":0}]}",
+            ),
+        );
+        assert_eq!(
+            graph.to_dot(&Rules::default()),
+            [
+                "graph schematic {",
+                "    n0 [label=\"12\\n(0, 0-1)\", shape=box, color=green];",
+                "    n1 [label=\"3\\n(1, 1-1)\", shape=box, color=green];",
+                "    s0 [label=\"\\\"\\n(1, 0)\", shape=diamond];",
+                "    n0 -- s0;",
+                "    n1 -- s0;",
+                "}",
+            ].join("\n"),
+        );
+    }
+}
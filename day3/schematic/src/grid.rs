@@ -0,0 +1,187 @@
+pub const NEIGHBOR_OFFSETS_4: [(isize, isize); 4] = [
+    (-1,  0),
+    ( 0, -1),
+    ( 0,  1),
+    ( 1,  0),
+];
+
+pub const NEIGHBOR_OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1,  0),
+    (-1,  1),
+    ( 0, -1),
+    ( 0,  1),
+    ( 1, -1),
+    ( 1,  0),
+    ( 1,  1),
+];
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, String> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width*height);
+        for (row_idx, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(format!(
+                    "Row {} has length {}, expected {}!", row_idx + 1, row.len(), width,
+                ));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {width, height, cells})
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Grid<T> {
+        let mut cells = Vec::with_capacity(width*height);
+        for row in 0..height {
+            for col in 0..width {
+                cells.push(f(row, col));
+            }
+        }
+        Grid {width, height, cells}
+    }
+
+    pub fn parse(text: &str, parse_cell: impl Fn(char) -> T) -> Result<Grid<T>, String> {
+        Grid::from_rows(text.lines().map(|l| l.chars().map(&parse_cell).collect()).collect())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.idx(row, col).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.idx(row, col).map(|idx| &mut self.cells[idx])
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> Result<(), String> {
+        let cell = self.get_mut(row, col)
+            .ok_or(format!("Cell ({}, {}) is out of bounds!", row, col))?;
+        *cell = value;
+        Ok(())
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row >= self.height {
+            return None;
+        }
+        Some(&self.cells[row*self.width..(row + 1)*self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| &self.cells[row*self.width..(row + 1)*self.width])
+    }
+
+    pub fn column(&self, col: usize) -> Option<impl Iterator<Item = &T>> {
+        if col >= self.width {
+            return None;
+        }
+        Some(self.cells.iter().skip(col).step_by(self.width))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {width: self.width, height: self.height, cells: self.cells.iter().map(f).collect()}
+    }
+
+    pub fn offset(&self, row: usize, col: usize, offset: (isize, isize)) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(offset.0)?;
+        let col = col.checked_add_signed(offset.1)?;
+        self.idx(row, col).map(|_| (row, col))
+    }
+
+    pub fn neighbors_with<'a>(
+        &'a self,
+        row: usize,
+        col: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |offset| self.offset(row, col, *offset))
+    }
+
+    pub fn neighbors4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors_with(row, col, &NEIGHBOR_OFFSETS_4)
+    }
+
+    pub fn neighbors8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors_with(row, col, &NEIGHBOR_OFFSETS_8)
+    }
+
+    fn idx(&self, row: usize, col: usize) -> Option<usize> {
+        if row < self.height && col < self.width {
+            Some(row*self.width + col)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {width, height, cells: vec![fill; width*height]}
+    }
+
+    // Copies the rows `row_start..row_end` that are inside the grid.
+    pub fn sub_rows(&self, row_start: usize, row_end: usize) -> Grid<T> {
+        let row_end = row_end.min(self.height);
+        let row_start = row_start.min(row_end);
+        Grid {
+            width: self.width,
+            height: row_end - row_start,
+            cells: self.cells[row_start*self.width..row_end*self.width].to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+
+    #[test]
+    fn test_parse_and_views() {
+        let grid = Grid::parse("abc\ndef", |c| c).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(1, 2), Some(&'f'));
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.row(0), Some(&['a', 'b', 'c'][..]));
+        assert_eq!(grid.column(1).unwrap().collect::<String>(), "be");
+        assert!(grid.column(3).is_none());
+        assert_eq!(grid.sub_rows(1, 5), Grid::parse("def", |c| c).unwrap());
+        assert_eq!(grid.sub_rows(3, 5).height(), 0);
+        assert_eq!(
+            Grid::parse("abc\nde", |c| c),
+            Err("Row 2 has length 2, expected 3!".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbors8(0, 0).collect::<Vec<_>>(), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(grid.neighbors4(2, 2).collect::<Vec<_>>(), vec![(1, 2), (2, 1)]);
+        assert_eq!(grid.neighbors8(1, 1).count(), 8);
+        assert_eq!(
+            grid.neighbors_with(0, 2, &[(0, -2), (0, 1), (2, 0)]).collect::<Vec<_>>(),
+            vec![(0, 0), (2, 2)],
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{grid::Grid, rules::Rules, tokens::TokenizedSchematic};
+
+// Keeps the part number and gear ratio sums up to date while cells are edited.
+// Numbers and gears only look at the rows next to their own, so an edit only
+// revisits the edited row and the rows above and below it.
+pub struct IncrementalSchematic {
+    rules: Rules,
+    grid: Grid<char>,
+    // Part number and gear ratio sum of every row.
+    row_sums: Vec<(usize, usize)>,
+    part_number_sum: usize,
+    gear_ratio_sum: usize,
+}
+
+impl IncrementalSchematic {
+    pub fn new(grid: Grid<char>, rules: Rules) -> IncrementalSchematic {
+        let row_sums: Vec<(usize, usize)> = (0..grid.height())
+            .map(|row| row_sums(&grid, row, &rules))
+            .collect();
+        IncrementalSchematic {
+            part_number_sum: row_sums.iter().map(|sums| sums.0).sum(),
+            gear_ratio_sum: row_sums.iter().map(|sums| sums.1).sum(),
+            rules,
+            grid,
+            row_sums,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    pub fn part_number_sum(&self) -> usize {
+        self.part_number_sum
+    }
+
+    pub fn gear_ratio_sum(&self) -> usize {
+        self.gear_ratio_sum
+    }
+
+    pub fn set_cell(&mut self, row: usize, col: usize, c: char) -> Result<(), String> {
+        if self.grid.get(row, col) == Some(&c) {
+            return Ok(());
+        }
+        self.grid.set(row, col, c)?;
+        for changed_row in row.saturating_sub(1)..(row + 2).min(self.grid.height()) {
+            let (old_part_sum, old_gear_sum) = self.row_sums[changed_row];
+            let (part_sum, gear_sum) = row_sums(&self.grid, changed_row, &self.rules);
+            self.part_number_sum = self.part_number_sum - old_part_sum + part_sum;
+            self.gear_ratio_sum = self.gear_ratio_sum - old_gear_sum + gear_sum;
+            self.row_sums[changed_row] = (part_sum, gear_sum);
+        }
+        Ok(())
+    }
+}
+
+// Tokenizes only `row` and its neighboring rows.
+fn row_sums(grid: &Grid<char>, row: usize, rules: &Rules) -> (usize, usize) {
+    let row_start = row.saturating_sub(1);
+    let schematic = TokenizedSchematic::tokenize(&grid.sub_rows(row_start, row + 2), rules);
+    let window_row = row - row_start;
+    (
+        schematic.part_numbers_in_row(window_row, rules).map(|number| number.value).sum(),
+        schematic.gear_ratios_in_row(window_row, rules).map(|(_, ratio)| ratio).sum(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use crate::{
+        grid::Grid, incremental::IncrementalSchematic, rules::Rules,
+        testing::{random_cell, random_schematic}, tokens::TokenizedSchematic,
+    };
+
+    #[test]
+    fn test_set_cell() {
+        let grid = Grid::parse("12.34\n.....", |c| c).unwrap();
+        let mut schematic = IncrementalSchematic::new(grid, Rules::default());
+        assert_eq!((schematic.part_number_sum(), schematic.gear_ratio_sum()), (0, 0));
+        schematic.set_cell(1, 2, '*').unwrap();
+        assert_eq!((schematic.part_number_sum(), schematic.gear_ratio_sum()), (46, 408));
+        schematic.set_cell(0, 2, '5').unwrap();
+        assert_eq!((schematic.part_number_sum(), schematic.gear_ratio_sum()), (12534, 0));
+        schematic.set_cell(0, 0, '.').unwrap();
+        assert_eq!((schematic.part_number_sum(), schematic.gear_ratio_sum()), (2534, 0));
+        assert!(schematic.set_cell(2, 0, '*').is_err());
+    }
+
+    #[test]
+    fn test_set_cell_matches_full_recomputation() {
+        let mut rng = Rng::new(37);
+        let rules = Rules::default();
+        for _ in 0..50 {
+            let height = 1 + rng.below(7);
+            let width = 1 + rng.below(7);
+            let grid = Grid::parse(&random_schematic(&mut rng, height, width), |c| c).unwrap();
+            let mut schematic = IncrementalSchematic::new(grid, rules.clone());
+            for _ in 0..40 {
+                let (row, col) = (rng.below(height), rng.below(width));
+                schematic.set_cell(row, col, random_cell(&mut rng)).unwrap();
+                let tokenized = TokenizedSchematic::tokenize(schematic.grid(), &rules);
+                assert_eq!(
+                    (schematic.part_number_sum(), schematic.gear_ratio_sum()),
+                    (tokenized.part_number_sum(&rules), tokenized.gear_ratio_sum(&rules)),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use common::args::flag_value;
+
+use crate::grid::Grid;
+
+pub const RAGGED_FLAGS: &[&str] = &["--ragged="];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RaggedPolicy {
+    // Every row must be as long as the first one.
+    Reject,
+    // Rows shorter than the first one are padded with blanks, longer rows are rejected.
+    Pad,
+    // The grid is as wide as the longest row and missing cells are blank.
+    MissingAsBlank,
+}
+
+impl RaggedPolicy {
+    pub fn from_args(args: &[String]) -> Result<RaggedPolicy, String> {
+        match flag_value(args, "--ragged")? {
+            None | Some("reject") => Ok(RaggedPolicy::Reject),
+            Some("pad") => Ok(RaggedPolicy::Pad),
+            Some("blank") => Ok(RaggedPolicy::MissingAsBlank),
+            Some(value) => Err(format!("Unknown ragged row policy '{}'!", value)),
+        }
+    }
+}
+
+// Strips the '\r' left over from CRLF line endings.
+pub fn normalize_line(line: &str) -> &str {
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+pub fn parse_text(text: &str, policy: RaggedPolicy, blank: char) -> Result<Grid<char>, String> {
+    parse_lines(text.lines(), policy, blank)
+}
+
+pub fn parse_lines<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    policy: RaggedPolicy,
+    blank: char,
+) -> Result<Grid<char>, String> {
+    let mut rows = lines.enumerate()
+        .map(|(row_idx, line)| parse_row(row_idx, line))
+        .collect::<Result<Vec<Vec<char>>, String>>()?;
+    let expected_width = rows.first().map_or(0, |row| row.len());
+    let width = match policy {
+        RaggedPolicy::MissingAsBlank => rows.iter().map(|row| row.len()).max().unwrap_or(0),
+        RaggedPolicy::Reject | RaggedPolicy::Pad => expected_width,
+    };
+    for (row_idx, row) in rows.iter_mut().enumerate() {
+        fit_row(row_idx, row, width, policy, blank)?;
+    }
+    Grid::from_rows(rows)
+}
+
+pub fn parse_row(row_idx: usize, line: &str) -> Result<Vec<char>, String> {
+    let row: Vec<char> = normalize_line(line).chars().collect();
+    if let Some(col_idx) = row.iter().position(|c| c.is_control()) {
+        return Err(format!(
+            "Row {}, column {}: unexpected control character {:?}!",
+            row_idx + 1, col_idx + 1, row[col_idx],
+        ));
+    }
+    Ok(row)
+}
+
+// Checks the row against the grid width and pads it with blanks where the policy allows.
+pub fn fit_row(
+    row_idx: usize,
+    row: &mut Vec<char>,
+    width: usize,
+    policy: RaggedPolicy,
+    blank: char,
+) -> Result<(), String> {
+    let is_invalid = match policy {
+        RaggedPolicy::Reject => row.len() != width,
+        RaggedPolicy::Pad => row.len() > width,
+        RaggedPolicy::MissingAsBlank => false,
+    };
+    if is_invalid {
+        return Err(format!(
+            "Row {} has length {}, expected {}!", row_idx + 1, row.len(), width,
+        ));
+    }
+    row.resize(width.max(row.len()), blank);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{parse_text, RaggedPolicy};
+
+    #[test]
+    fn test_parse_text_policies() {
+        let text = "12.\r\n.*\r\n3..\r\n";
+        assert_eq!(
+            parse_text(text, RaggedPolicy::Reject, '.'),
+            Err("Row 2 has length 2, expected 3!".to_string()),
+        );
+        let grid = parse_text(text, RaggedPolicy::Pad, '.').unwrap();
+        assert_eq!(grid.row(1), Some(&['.', '*', '.'][..]));
+        assert_eq!(grid.height(), 3);
+        assert_eq!(
+            parse_text("1.\n2..*", RaggedPolicy::Pad, '.'),
+            Err("Row 2 has length 4, expected 2!".to_string()),
+        );
+        let grid = parse_text("1.\n2..*", RaggedPolicy::MissingAsBlank, '_').unwrap();
+        assert_eq!(grid.row(0), Some(&['1', '.', '_', '_'][..]));
+    }
+
+    #[test]
+    fn test_parse_text_rejects_control_characters() {
+        assert_eq!(
+            parse_text("1..\n.\t.", RaggedPolicy::Reject, '.'),
+            Err("Row 2, column 2: unexpected control character '\\t'!".to_string()),
+        );
+        assert_eq!(parse_text("467", RaggedPolicy::Reject, '.').map(|g| g.height()), Ok(1));
+    }
+}
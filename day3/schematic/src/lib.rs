@@ -0,0 +1,13 @@
+pub mod clusters;
+pub mod graph;
+pub mod grid;
+pub mod incremental;
+pub mod input;
+pub mod numbers;
+pub mod render;
+pub mod rules;
+pub mod stream;
+pub mod tokens;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,103 @@
+use crate::grid::Grid;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Number {
+    pub value: usize,
+    pub row: usize,
+    pub col_start: usize,
+    // Exclusive.
+    pub col_end: usize,
+}
+
+impl Number {
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        row == self.row && self.col_start <= col && col < self.col_end
+    }
+
+    pub fn adjacent_positions<T>(&self, grid: &Grid<T>) -> Vec<(usize, usize)> {
+        let mut positions = Vec::new();
+        for col in self.col_start..self.col_end {
+            for pos in grid.neighbors8(self.row, col) {
+                if !self.contains(pos.0, pos.1) && !positions.contains(&pos) {
+                    positions.push(pos);
+                }
+            }
+        }
+        positions
+    }
+}
+
+// Numbers never continue from the end of one row onto the start of the next.
+pub fn find_numbers(grid: &Grid<char>) -> Vec<Number> {
+    grid.rows().enumerate()
+        .flat_map(|(row_idx, row)| find_numbers_in_row(row_idx, row))
+        .collect()
+}
+
+pub fn find_numbers_in_row(row_idx: usize, row: &[char]) -> Vec<Number> {
+    let mut numbers = Vec::new();
+    let mut current: Option<Number> = None;
+    for (col_idx, c) in row.iter().enumerate() {
+        match (c.to_digit(10), current.as_mut()) {
+            (Some(digit), Some(number)) => {
+                number.value = number.value*10 + digit as usize;
+                number.col_end = col_idx + 1;
+            }
+            (Some(digit), None) => {
+                current = Some(Number {
+                    value: digit as usize,
+                    row: row_idx,
+                    col_start: col_idx,
+                    col_end: col_idx + 1,
+                });
+            }
+            (None, _) => numbers.extend(current.take()),
+        }
+    }
+    numbers.extend(current);
+    numbers
+}
+
+pub fn number_idx_grid(grid: &Grid<char>, numbers: &[Number]) -> Grid<Option<usize>> {
+    let mut idx_grid = Grid::new(grid.width(), grid.height(), None);
+    for (num_idx, number) in numbers.iter().enumerate() {
+        for col in number.col_start..number.col_end {
+            // Numbers are found inside `grid`, so the cells are in bounds.
+            let _ = idx_grid.set(number.row, col, Some(num_idx));
+        }
+    }
+    idx_grid
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{grid::Grid, numbers::{find_numbers, number_idx_grid, Number}};
+
+    #[test]
+    fn test_find_numbers_does_not_cross_rows() {
+        let grid = Grid::parse("..12\n34..\n5..6", |c| c).unwrap();
+        assert_eq!(
+            find_numbers(&grid),
+            vec![
+                Number {value: 12, row: 0, col_start: 2, col_end: 4},
+                Number {value: 34, row: 1, col_start: 0, col_end: 2},
+                Number {value: 5, row: 2, col_start: 0, col_end: 1},
+                Number {value: 6, row: 2, col_start: 3, col_end: 4},
+            ],
+        );
+    }
+
+    #[test]
+    fn test_adjacent_positions() {
+        let grid = Grid::parse("12.\n...", |c| c).unwrap();
+        let numbers = find_numbers(&grid);
+        assert_eq!(
+            numbers[0].adjacent_positions(&grid),
+            vec![(1, 0), (1, 1), (0, 2), (1, 2)],
+        );
+        assert_eq!(
+            number_idx_grid(&grid, &numbers).row(0),
+            Some(&[Some(0), Some(0), None][..]),
+        );
+    }
+}
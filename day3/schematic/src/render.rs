@@ -0,0 +1,134 @@
+use crate::{grid::Grid, rules::Rules, tokens::{Token, TokenizedSchematic}};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const GEAR: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Viewport {
+    pub row_start: usize,
+    pub row_end: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+}
+
+impl Viewport {
+    // Parses `<start>..<end>` row and column ranges, either side may be empty.
+    pub fn parse(rows: Option<&str>, cols: Option<&str>) -> Result<Viewport, String> {
+        let (row_start, row_end) = parse_range(rows)?;
+        let (col_start, col_end) = parse_range(cols)?;
+        Ok(Viewport {row_start, row_end, col_start, col_end})
+    }
+
+    fn contains(&self, row: usize, col: usize) -> bool {
+        self.row_start <= row && row < self.row_end && self.col_start <= col && col < self.col_end
+    }
+}
+
+fn parse_range(s: Option<&str>) -> Result<(usize, usize), String> {
+    let Some(s) = s else {
+        return Ok((0, usize::MAX));
+    };
+    let (start, end) = s.split_once("..").ok_or(format!("Range '{}' must look like 'a..b'!", s))?;
+    let parse = |bound: &str, default: usize| if bound.is_empty() {
+        Ok(default)
+    } else {
+        bound.parse::<usize>().map_err(|_err| format!("Could not parse range '{}'!", s))
+    };
+    Ok((parse(start, 0)?, parse(end, usize::MAX)?))
+}
+
+pub fn render(
+    grid: &Grid<char>,
+    schematic: &TokenizedSchematic,
+    rules: &Rules,
+    viewport: &Viewport,
+) -> String {
+    let mut colors: Grid<Option<&str>> = Grid::new(grid.width(), grid.height(), None);
+    let mut panel = Vec::new();
+    for (token_idx, token) in schematic.tokens().iter().enumerate() {
+        let color = match token {
+            Token::Number(_) if schematic.is_part_number(token_idx, rules) => GREEN,
+            Token::Number(_) => RED,
+            Token::Symbol(_) => {
+                let (Some(ratio), Some((row, col))) = (
+                    schematic.gear_ratio(token_idx, rules), schematic.first_cell(token_idx),
+                ) else {
+                    continue;
+                };
+                if viewport.contains(row, col) {
+                    let adj_values: Vec<String> = schematic.adjacent_tokens(token_idx).iter()
+                        .filter_map(|adj_idx| match &schematic.tokens()[*adj_idx] {
+                            Token::Number(number) => Some(number.value.to_string()),
+                            _ => None,
+                        })
+                        .collect();
+                    panel.push(format!("gear ({}, {}): {} -> {}", row, col, adj_values.join(", "), ratio));
+                }
+                GEAR
+            }
+            Token::Blank => continue,
+        };
+        for (row, col) in schematic.cells(token_idx) {
+            let _ = colors.set(row, col, Some(color));
+        }
+    }
+
+    let row_end = viewport.row_end.min(grid.height());
+    let col_end = viewport.col_end.min(grid.width());
+    let mut lines = Vec::new();
+    for row in viewport.row_start..row_end {
+        let mut line = String::new();
+        let mut current_color = None;
+        for col in viewport.col_start..col_end {
+            let color = colors.get(row, col).copied().flatten();
+            if color != current_color {
+                line.push_str(color.unwrap_or(RESET));
+                current_color = color;
+            }
+            line.extend(grid.get(row, col));
+        }
+        if current_color.is_some() {
+            line.push_str(RESET);
+        }
+        lines.push(line);
+    }
+
+    let grid_width = col_end.saturating_sub(viewport.col_start);
+    for (line_idx, panel_line) in panel.into_iter().enumerate() {
+        if line_idx >= lines.len() {
+            lines.push(" ".repeat(grid_width));
+        }
+        lines[line_idx].push_str(" | ");
+        lines[line_idx].push_str(&panel_line);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{grid::Grid, render::{render, Viewport}, rules::Rules, tokens::TokenizedSchematic};
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::parse("12*3\n....\n45..", |c| c).unwrap();
+        let rules = Rules::default();
+        let schematic = TokenizedSchematic::tokenize(&grid, &rules);
+        let full = Viewport::parse(None, None).unwrap();
+        assert_eq!(
+            render(&grid, &schematic, &rules, &full),
+            [
+                "\x1b[32m12\x1b[1;33m*\x1b[32m3\x1b[0m | gear (0, 2): 12, 3 -> 36",
+                "....",
+                "\x1b[31m45\x1b[0m..",
+            ].join("\n"),
+        );
+        let viewport = Viewport::parse(Some("1.."), Some("..2")).unwrap();
+        assert_eq!(
+            render(&grid, &schematic, &rules, &viewport),
+            ["..", "\x1b[31m45\x1b[0m"].join("\n"),
+        );
+        assert!(Viewport::parse(Some("1-2"), None).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use common::args::flag_value;
+
+pub const RULE_FLAGS: &[&str] = &["--blanks=", "--symbols=", "--gears=", "--gear-count=", "--combine="];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AdjacentCount {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rules {
+    pub blanks: Vec<char>,
+    // `None` means every character that is neither a digit nor a blank.
+    pub symbols: Option<Vec<char>>,
+    pub gears: Vec<char>,
+    pub gear_adjacent_count: AdjacentCount,
+    pub gear_combine: Combine,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            blanks: vec!['.'],
+            symbols: None,
+            gears: vec!['*'],
+            gear_adjacent_count: AdjacentCount::Exactly(2),
+            gear_combine: Combine::Product,
+        }
+    }
+}
+
+impl Rules {
+    // Reads `--blanks=`, `--symbols=`, `--gears=`, `--gear-count=N` (or `N+`
+    // for at least N) and `--combine=product|sum|max`, ignoring other args.
+    pub fn from_args(args: &[String]) -> Result<Rules, String> {
+        let mut rules = Rules::default();
+        if let Some(value) = flag_value(args, "--blanks")? {
+            rules.blanks = value.chars().collect();
+        }
+        if let Some(value) = flag_value(args, "--symbols")? {
+            rules.symbols = Some(value.chars().collect());
+        }
+        if let Some(value) = flag_value(args, "--gears")? {
+            rules.gears = value.chars().collect();
+        }
+        if let Some(value) = flag_value(args, "--gear-count")? {
+            let (count_str, at_least) = match value.strip_suffix('+') {
+                Some(count_str) => (count_str, true),
+                None => (value, false),
+            };
+            let count = count_str.parse::<usize>()
+                .map_err(|_err| format!("Could not parse gear count '{}'!", value))?;
+            rules.gear_adjacent_count = if at_least {
+                AdjacentCount::AtLeast(count)
+            } else {
+                AdjacentCount::Exactly(count)
+            };
+        }
+        if let Some(value) = flag_value(args, "--combine")? {
+            rules.gear_combine = match value {
+                "product" => Combine::Product,
+                "sum" => Combine::Sum,
+                "max" => Combine::Max,
+                _ => return Err(format!("Unknown combine operation '{}'!", value)),
+            };
+        }
+        Ok(rules)
+    }
+
+    pub fn is_blank(&self, c: char) -> bool {
+        self.blanks.contains(&c)
+    }
+
+    // Used wherever a missing cell has to be filled in.
+    pub fn blank(&self) -> char {
+        self.blanks.first().copied().unwrap_or('.')
+    }
+
+    pub fn is_symbol(&self, c: char) -> bool {
+        match &self.symbols {
+            Some(symbols) => symbols.contains(&c),
+            None => !c.is_ascii_digit() && !self.is_blank(c),
+        }
+    }
+
+    pub fn is_gear(&self, c: char) -> bool {
+        self.gears.contains(&c)
+    }
+
+    pub fn accepts_gear_adjacent_count(&self, count: usize) -> bool {
+        match self.gear_adjacent_count {
+            AdjacentCount::Exactly(n) => count == n,
+            AdjacentCount::AtLeast(n) => count >= n,
+        }
+    }
+
+    pub fn combine(&self, nums: &[usize]) -> usize {
+        match self.gear_combine {
+            Combine::Product => nums.iter().product(),
+            Combine::Sum => nums.iter().sum(),
+            Combine::Max => nums.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::{AdjacentCount, Combine, Rules};
+
+    #[test]
+    fn test_default_rules() {
+        let rules = Rules::default();
+        assert!(rules.is_symbol('#') && rules.is_symbol('*'));
+        assert!(!rules.is_symbol('.') && !rules.is_symbol('7'));
+        assert!(rules.is_gear('*') && !rules.is_gear('#'));
+        assert!(rules.accepts_gear_adjacent_count(2) && !rules.accepts_gear_adjacent_count(3));
+        assert_eq!(rules.combine(&[3, 4]), 12);
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args: Vec<String> = ["input.txt", "--blanks=._", "--symbols=#*", "--gears=*#", "--gear-count=1+", "--combine=max"]
+            .iter().map(|arg| arg.to_string()).collect();
+        let rules = Rules::from_args(&args).unwrap();
+        assert_eq!(rules, Rules {
+            blanks: vec!['.', '_'],
+            symbols: Some(vec!['#', '*']),
+            gears: vec!['*', '#'],
+            gear_adjacent_count: AdjacentCount::AtLeast(1),
+            gear_combine: Combine::Max,
+        });
+        assert!(!rules.is_symbol('$'));
+        assert_eq!(rules.combine(&[3, 9, 4]), 9);
+        assert!(Rules::from_args(&["--combine=min".to_string()]).is_err());
+        assert_eq!(
+            Rules::from_args(&["--gear-count".to_string(), "3".to_string()]),
+            Err("Flag --gear-count needs a value, e.g. --gear-count=<value>!".to_string()),
+        );
+    }
+}
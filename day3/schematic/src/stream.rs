@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+
+use crate::{
+    grid::Grid, input::{fit_row, parse_row, RaggedPolicy}, numbers::Number, rules::Rules,
+    tokens::TokenizedSchematic,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    PartNumber(Number),
+    GearRatio {row: usize, col: usize, ratio: usize},
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct Totals {
+    pub part_number_sum: usize,
+    pub gear_ratio_sum: usize,
+}
+
+impl Totals {
+    pub fn add(&mut self, event: &Event) {
+        match event {
+            Event::PartNumber(number) => self.part_number_sum += number.value,
+            Event::GearRatio {ratio, ..} => self.gear_ratio_sum += ratio,
+        }
+    }
+}
+
+struct WindowRow {
+    row_idx: usize,
+    chars: Vec<char>,
+}
+
+// Keeps at most three rows in memory. A row is only complete once the row
+// below it is known, so events for row `r` are emitted when row `r + 1` is
+// pushed (or on `finish` for the last row).
+pub struct StreamingSolver {
+    rules: Rules,
+    policy: RaggedPolicy,
+    window: VecDeque<WindowRow>,
+    next_row_idx: usize,
+    width: Option<usize>,
+}
+
+impl StreamingSolver {
+    // The width is fixed by the first row, so rows can not be widened later on.
+    pub fn new(rules: Rules, policy: RaggedPolicy) -> Result<StreamingSolver, String> {
+        if policy == RaggedPolicy::MissingAsBlank {
+            return Err(
+                "Streaming needs the width of the first row, use --ragged=reject or --ragged=pad!".to_string()
+            );
+        }
+        Ok(StreamingSolver {rules, policy, window: VecDeque::with_capacity(3), next_row_idx: 0, width: None})
+    }
+
+    pub fn push_row(&mut self, line: &str) -> Result<Vec<Event>, String> {
+        let mut chars = parse_row(self.next_row_idx, line)?;
+        let width = *self.width.get_or_insert(chars.len());
+        fit_row(self.next_row_idx, &mut chars, width, self.policy, self.rules.blank())?;
+        self.window.push_back(WindowRow {row_idx: self.next_row_idx, chars});
+        self.next_row_idx += 1;
+
+        let len = self.window.len();
+        if len < 2 {
+            return Ok(Vec::new());
+        }
+        let events = self.process(len - 2);
+        if len == 3 {
+            self.window.pop_front();
+        }
+        Ok(events)
+    }
+
+    pub fn finish(self) -> Vec<Event> {
+        match self.window.len() {
+            0 => Vec::new(),
+            len => self.process(len - 1),
+        }
+    }
+
+    // Tokenizes the window, which holds every row next to `window_idx`.
+    fn process(&self, window_idx: usize) -> Vec<Event> {
+        let grid = Grid::from_fn(self.width.unwrap_or(0), self.window.len(), |row, col| {
+            self.window[row].chars[col]
+        });
+        let schematic = TokenizedSchematic::tokenize(&grid, &self.rules);
+        let row_idx = self.window[window_idx].row_idx;
+        let mut events: Vec<Event> = schematic.part_numbers_in_row(window_idx, &self.rules)
+            .map(|number| Event::PartNumber(Number {row: row_idx, ..number.clone()}))
+            .collect();
+        events.extend(
+            schematic.gear_ratios_in_row(window_idx, &self.rules)
+                .map(|(col, ratio)| Event::GearRatio {row: row_idx, col, ratio}),
+        );
+        events
+    }
+}
+
+// Stops at the first line that could not be read.
+pub fn solve_lines(
+    lines: impl Iterator<Item = Result<String, String>>,
+    policy: RaggedPolicy,
+    rules: &Rules,
+    mut on_event: impl FnMut(&Event),
+) -> Result<Totals, String> {
+    let mut solver = StreamingSolver::new(rules.clone(), policy)?;
+    let mut totals = Totals::default();
+    for line in lines {
+        for event in solver.push_row(&line?)? {
+            totals.add(&event);
+            on_event(&event);
+        }
+    }
+    for event in solver.finish() {
+        totals.add(&event);
+        on_event(&event);
+    }
+    Ok(totals)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use crate::{
+        grid::Grid, input::{parse_text, RaggedPolicy}, rules::Rules,
+        stream::{solve_lines, Event, StreamingSolver, Totals},
+        testing::random_schematic, tokens::TokenizedSchematic,
+    };
+
+    #[test]
+    fn test_streaming_solver_emits_events_per_row() {
+        let mut solver = StreamingSolver::new(Rules::default(), RaggedPolicy::Reject).unwrap();
+        assert_eq!(solver.push_row("467..114.."), Ok(vec![]));
+        let events = solver.push_row("...*......").unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], Event::PartNumber(number) if number.value == 467));
+        let events = solver.push_row("..35..633.").unwrap();
+        assert_eq!(events, vec![Event::GearRatio {row: 1, col: 3, ratio: 16345}]);
+        assert!(solver.push_row("..").is_err());
+        let lines = vec![Ok("1*".to_string()), Err("stream did not contain valid UTF-8".to_string())];
+        assert_eq!(
+            solve_lines(lines.into_iter(), RaggedPolicy::Reject, &Rules::default(), |_| {}),
+            Err("stream did not contain valid UTF-8".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_streaming_follows_ragged_policy() {
+        let solve = |input: &str, policy| {
+            solve_lines(input.lines().map(|l| Ok(l.to_string())), policy, &Rules::default(), |_| {})
+        };
+        let input = "467.\r\n..*\n12\n";
+        assert_eq!(solve(input, RaggedPolicy::Reject), Err("Row 2 has length 3, expected 4!".to_string()));
+        let grid = parse_text(input, RaggedPolicy::Pad, '.').unwrap();
+        let schematic = TokenizedSchematic::tokenize(&grid, &Rules::default());
+        assert_eq!(
+            solve(input, RaggedPolicy::Pad),
+            Ok(Totals {part_number_sum: schematic.part_number_sum(&Rules::default()), gear_ratio_sum: 467 * 12}),
+        );
+        assert_eq!(solve("1..\n", RaggedPolicy::Pad), Ok(Totals::default()));
+        assert_eq!(solve("12\n123", RaggedPolicy::Pad), Err("Row 2 has length 3, expected 2!".to_string()));
+        assert_eq!(
+            solve("1..\n.\t.", RaggedPolicy::Pad),
+            Err("Row 2, column 2: unexpected control character '\\t'!".to_string()),
+        );
+        assert!(solve("1..", RaggedPolicy::MissingAsBlank).is_err());
+    }
+
+    #[test]
+    fn test_streaming_matches_in_memory() {
+        let mut rng = Rng::new(36);
+        for _ in 0..300 {
+            let height = rng.below(8);
+            let width = 1 + rng.below(8);
+            let input = random_schematic(&mut rng, height, width);
+            let rules = Rules::default();
+            let lines = input.lines().map(|l| Ok(l.to_string()));
+            let totals = solve_lines(lines, RaggedPolicy::Reject, &rules, |_| {}).unwrap();
+            let schematic = TokenizedSchematic::tokenize(&Grid::parse(&input, |c| c).unwrap(), &rules);
+            assert_eq!(
+                totals,
+                Totals {
+                    part_number_sum: schematic.part_number_sum(&rules),
+                    gear_ratio_sum: schematic.gear_ratio_sum(&rules),
+                },
+                "{}", input,
+            );
+        }
+    }
+}
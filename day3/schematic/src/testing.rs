@@ -0,0 +1,18 @@
+// Shared helpers for randomized tests.
+use common::rng::Rng;
+
+pub fn random_cell(rng: &mut Rng) -> char {
+    match rng.below(10) {
+        0..=4 => '.',
+        5..=7 => char::from(b'0' + rng.below(10) as u8),
+        8 => '*',
+        _ => ['#', '$', '+', '/'][rng.below(4)],
+    }
+}
+
+pub fn random_schematic(rng: &mut Rng, height: usize, width: usize) -> String {
+    (0..height)
+        .map(|_| (0..width).map(|_| random_cell(rng)).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,316 @@
+use std::ops::Range;
+
+use crate::{grid::Grid, numbers::{find_numbers, number_idx_grid, Number}, rules::Rules};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Token {
+    Number(Number),
+    Symbol(char),
+    Blank,
+}
+
+// Every cell points at a token and all cells of a number share the same one.
+// Tokens are in reading order of their first cell.
+#[derive(Debug, PartialEq)]
+pub struct TokenizedSchematic {
+    idx_grid: Grid<usize>,
+    tokens: Vec<Token>,
+    first_cells: Vec<(usize, usize)>,
+}
+
+impl TokenizedSchematic {
+    pub fn tokenize(grid: &Grid<char>, rules: &Rules) -> TokenizedSchematic {
+        let numbers = find_numbers(grid);
+        let num_idx_grid = number_idx_grid(grid, &numbers);
+        let mut tokens = Vec::<Token>::new();
+        let mut first_cells = Vec::<(usize, usize)>::new();
+        let mut num_token_idxs = vec![None; numbers.len()];
+        let idx_grid = Grid::from_fn(grid.width(), grid.height(), |row, col| {
+            if let Some(Some(num_idx)) = num_idx_grid.get(row, col) {
+                return *num_token_idxs[*num_idx].get_or_insert_with(|| {
+                    tokens.push(Token::Number(numbers[*num_idx].clone()));
+                    first_cells.push((row, col));
+                    tokens.len() - 1
+                });
+            }
+            let c = grid.get(row, col).copied().unwrap_or_else(|| rules.blank());
+            // Characters explicitly listed as symbols or gears win over blanks.
+            tokens.push(if rules.is_blank(c) && !rules.is_symbol(c) && !rules.is_gear(c) {
+                Token::Blank
+            } else {
+                Token::Symbol(c)
+            });
+            first_cells.push((row, col));
+            tokens.len() - 1
+        });
+        TokenizedSchematic {idx_grid, tokens, first_cells}
+    }
+
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    pub fn idx_grid(&self) -> &Grid<usize> {
+        &self.idx_grid
+    }
+
+    pub fn token_at(&self, row: usize, col: usize) -> Option<&Token> {
+        self.tokens.get(*self.idx_grid.get(row, col)?)
+    }
+
+    pub fn first_cell(&self, token_idx: usize) -> Option<(usize, usize)> {
+        self.first_cells.get(token_idx).copied()
+    }
+
+    // Tokens never span rows, so the tokens of a row are one run of indices.
+    pub fn row_tokens(&self, row: usize) -> Range<usize> {
+        match self.idx_grid.row(row).map(|idxs| (idxs.first(), idxs.last())) {
+            Some((Some(first), Some(last))) => *first..*last + 1,
+            _ => 0..0,
+        }
+    }
+
+    pub fn cells(&self, token_idx: usize) -> Vec<(usize, usize)> {
+        match self.tokens.get(token_idx) {
+            Some(Token::Number(number)) => {
+                (number.col_start..number.col_end).map(|col| (number.row, col)).collect()
+            }
+            Some(_) => vec![self.first_cells[token_idx]],
+            None => Vec::new(),
+        }
+    }
+
+    pub fn adjacent_tokens(&self, token_idx: usize) -> Vec<usize> {
+        let mut adj_idxs = Vec::new();
+        for (row, col) in self.cells(token_idx) {
+            for (adj_row, adj_col) in self.idx_grid.neighbors8(row, col) {
+                let Some(adj_idx) = self.idx_grid.get(adj_row, adj_col) else {
+                    continue;
+                };
+                if *adj_idx != token_idx && !adj_idxs.contains(adj_idx) {
+                    adj_idxs.push(*adj_idx);
+                }
+            }
+        }
+        adj_idxs
+    }
+
+    pub fn numbers(&self) -> impl Iterator<Item = (usize, &Number)> {
+        self.tokens.iter().enumerate().filter_map(|(token_idx, token)| match token {
+            Token::Number(number) => Some((token_idx, number)),
+            _ => None,
+        })
+    }
+
+    pub fn is_part_number(&self, token_idx: usize, rules: &Rules) -> bool {
+        if !matches!(self.tokens.get(token_idx), Some(Token::Number(_))) {
+            return false;
+        }
+        self.adjacent_tokens(token_idx).iter()
+            .any(|adj_idx| matches!(self.tokens[*adj_idx], Token::Symbol(c) if rules.is_symbol(c)))
+    }
+
+    pub fn gear_ratio(&self, token_idx: usize, rules: &Rules) -> Option<usize> {
+        match self.tokens.get(token_idx)? {
+            Token::Symbol(c) if rules.is_gear(*c) => {}
+            _ => return None,
+        }
+        let adj_nums: Vec<usize> = self.adjacent_tokens(token_idx).iter()
+            .filter_map(|adj_idx| match &self.tokens[*adj_idx] {
+                Token::Number(number) => Some(number.value),
+                _ => None,
+            })
+            .collect();
+        if !rules.accepts_gear_adjacent_count(adj_nums.len()) {
+            return None;
+        }
+        Some(rules.combine(&adj_nums))
+    }
+
+    // Only the rows above and below `row` decide these, which is what lets the
+    // streaming and incremental solvers tokenize three rows at a time.
+    pub fn part_numbers_in_row<'a>(&'a self, row: usize, rules: &'a Rules) -> impl Iterator<Item = &'a Number> {
+        self.row_tokens(row).filter_map(move |token_idx| match &self.tokens[token_idx] {
+            Token::Number(number) if self.is_part_number(token_idx, rules) => Some(number),
+            _ => None,
+        })
+    }
+
+    // Yields `(col, ratio)` for every gear in `row`.
+    pub fn gear_ratios_in_row<'a>(
+        &'a self,
+        row: usize,
+        rules: &'a Rules,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.row_tokens(row).filter_map(move |token_idx| {
+            let ratio = self.gear_ratio(token_idx, rules)?;
+            Some((self.first_cells[token_idx].1, ratio))
+        })
+    }
+
+    pub fn part_number_sum(&self, rules: &Rules) -> usize {
+        self.numbers()
+            .filter(|(token_idx, _)| self.is_part_number(*token_idx, rules))
+            .map(|(_, number)| number.value)
+            .sum()
+    }
+
+    pub fn gear_ratio_sum(&self, rules: &Rules) -> usize {
+        (0..self.tokens.len())
+            .filter_map(|token_idx| self.gear_ratio(token_idx, rules))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use crate::{
+        grid::Grid, numbers::Number,
+        rules::{AdjacentCount, Combine, Rules},
+        testing::random_schematic,
+        tokens::{Token, TokenizedSchematic},
+    };
+
+    // The implementations part1 and part2 used before they moved onto `TokenizedSchematic`.
+    mod legacy {
+        use crate::{grid::Grid, numbers::{find_numbers, number_idx_grid}, rules::Rules};
+
+        #[derive(Debug, PartialEq)]
+        enum Token {
+            Num(usize),
+            Gear,
+            Unknown,
+        }
+
+        struct TokenMatrix {
+            idx_matrix: Grid<usize>,
+            tokens: Vec<Token>,
+        }
+
+        // Part1 marked every number next to a symbol while building its `AdjacencyGraph`.
+        pub fn sum_part_numbers(chars_matrix: &Grid<char>, rules: &Rules) -> usize {
+            let numbers = find_numbers(chars_matrix);
+            let num_idx_matrix = number_idx_grid(chars_matrix, &numbers);
+            let mut is_part = vec![false; numbers.len()];
+            for (row, col) in chars_matrix.positions() {
+                match chars_matrix.get(row, col) {
+                    Some(c) if !c.is_ascii_digit() && rules.is_symbol(*c) => {}
+                    _ => continue,
+                }
+                for (adj_row, adj_col) in chars_matrix.neighbors8(row, col) {
+                    if let Some(Some(num_idx)) = num_idx_matrix.get(adj_row, adj_col) {
+                        is_part[*num_idx] = true;
+                    }
+                }
+            }
+            numbers.iter().zip(is_part)
+                .filter(|(_, is_part)| *is_part)
+                .map(|(number, _)| number.value)
+                .sum()
+        }
+
+        pub fn sum_gear_ratios(chars_matrix: &Grid<char>, rules: &Rules) -> usize {
+            let token_matrix = tokenize(chars_matrix, rules);
+            token_matrix.idx_matrix.positions()
+                .filter_map(|(row, col)| gear_ratio_at_cell(&token_matrix, rules, row, col))
+                .sum()
+        }
+
+        fn tokenize(chars_matrix: &Grid<char>, rules: &Rules) -> TokenMatrix {
+            let numbers = find_numbers(chars_matrix);
+            let num_idx_matrix = number_idx_grid(chars_matrix, &numbers);
+            let mut tokens = Vec::<Token>::new();
+            let mut num_token_idxs = vec![None; numbers.len()];
+            let idx_matrix = Grid::from_fn(chars_matrix.width(), chars_matrix.height(), |row, col| {
+                if let Some(Some(num_idx)) = num_idx_matrix.get(row, col) {
+                    return *num_token_idxs[*num_idx].get_or_insert_with(|| {
+                        tokens.push(Token::Num(numbers[*num_idx].value));
+                        tokens.len() - 1
+                    });
+                }
+                tokens.push(match chars_matrix.get(row, col) {
+                    Some(c) if rules.is_gear(*c) => Token::Gear,
+                    _ => Token::Unknown,
+                });
+                tokens.len() - 1
+            });
+            TokenMatrix {idx_matrix, tokens}
+        }
+
+        fn gear_ratio_at_cell(
+            token_matrix: &TokenMatrix,
+            rules: &Rules,
+            row: usize,
+            col: usize,
+        ) -> Option<usize> {
+            let token_idx = token_matrix.idx_matrix.get(row, col)?;
+            if token_matrix.tokens[*token_idx] != Token::Gear {
+                return None;
+            }
+            let mut adj_nums = Vec::<usize>::new();
+            let mut encountered_adj_num_idxs = Vec::<usize>::new();
+            for (adj_row, adj_col) in token_matrix.idx_matrix.neighbors8(row, col) {
+                let adj_token_idx = token_matrix.idx_matrix.get(adj_row, adj_col)?;
+                if let Token::Num(adj_num) = token_matrix.tokens[*adj_token_idx] {
+                    if encountered_adj_num_idxs.contains(adj_token_idx) {
+                        continue;
+                    }
+                    adj_nums.push(adj_num);
+                    encountered_adj_num_idxs.push(*adj_token_idx);
+                }
+            }
+            if !rules.accepts_gear_adjacent_count(adj_nums.len()) {
+                return None;
+            }
+            Some(rules.combine(&adj_nums))
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let grid = Grid::parse("467..\n...*.\n..35#", |c| c).unwrap();
+        let schematic = TokenizedSchematic::tokenize(&grid, &Rules::default());
+        assert_eq!(schematic.idx_grid().row(0), Some(&[0, 0, 0, 1, 2][..]));
+        assert_eq!(schematic.idx_grid().row(2), Some(&[8, 9, 10, 10, 11][..]));
+        assert_eq!(schematic.tokens()[0], Token::Number(Number {value: 467, row: 0, col_start: 0, col_end: 3}));
+        assert_eq!(schematic.token_at(1, 3), Some(&Token::Symbol('*')));
+        assert_eq!(schematic.token_at(2, 4), Some(&Token::Symbol('#')));
+        assert_eq!(schematic.token_at(2, 0), Some(&Token::Blank));
+        assert_eq!(schematic.gear_ratio(6, &Rules::default()), Some(16345));
+        assert_eq!(schematic.gear_ratio(11, &Rules::default()), None);
+        assert_eq!(schematic.row_tokens(1), 3..8);
+        assert_eq!(schematic.first_cell(10), Some((2, 2)));
+        let part_values: Vec<usize> = schematic.part_numbers_in_row(2, &Rules::default()).map(|n| n.value).collect();
+        assert_eq!(part_values, vec![35]);
+        assert_eq!(schematic.gear_ratios_in_row(1, &Rules::default()).collect::<Vec<_>>(), vec![(3, 16345)]);
+        assert_eq!(schematic.part_number_sum(&Rules::default()), 502);
+        assert_eq!(schematic.gear_ratio_sum(&Rules::default()), 16345);
+    }
+
+    #[test]
+    fn test_tokenize_matches_old_implementations() {
+        let mut rng = Rng::new(39);
+        let rule_sets = [
+            Rules::default(),
+            Rules {
+                symbols: Some(vec!['*', '#']),
+                gears: vec!['*', '$'],
+                gear_adjacent_count: AdjacentCount::AtLeast(1),
+                gear_combine: Combine::Sum,
+                ..Rules::default()
+            },
+        ];
+        for _ in 0..300 {
+            let height = rng.below(8);
+            let width = 1 + rng.below(8);
+            let grid = Grid::parse(&random_schematic(&mut rng, height, width), |c| c).unwrap();
+            for rules in &rule_sets {
+                let schematic = TokenizedSchematic::tokenize(&grid, rules);
+                assert_eq!(schematic.part_number_sum(rules), legacy::sum_part_numbers(&grid, rules));
+                assert_eq!(schematic.gear_ratio_sum(rules), legacy::sum_gear_ratios(&grid, rules));
+            }
+        }
+    }
+}
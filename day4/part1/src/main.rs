@@ -1,86 +1,136 @@
-use std::{fs::File, io::Read};
+use std::{env, fs::File, io::Read};
 
-struct Card {
-    winning_nums: Vec<usize>,
-    our_nums: Vec<usize>,
-}
+use common::args::check_flags;
+use scratchcards::{
+    card::{parse, validate_ids, Card, IdPolicy, ID_FLAGS},
+    matching::{count_matches, DuplicatePolicy, DUPLICATE_FLAGS},
+    scoring::{ScoringRule, SCORING_FLAGS}, strict::{parse_strict, StrictOptions, STRICT_FLAGS},
+};
 
 fn main() -> Result<(), String> {
-    let mut file = File::open("input.txt").map_err(|err| err.to_string())?;
-    let mut input = String::new();
-    let _ = file.read_to_string(&mut input);
-    println!(
-        "Total points: {}", 
-        score_input(&input).expect("Failed to parse input!")
-    );
+    let args: Vec<String> = env::args().skip(1).collect();
+    check_flags(&args, &[ID_FLAGS, DUPLICATE_FLAGS, SCORING_FLAGS, STRICT_FLAGS])?;
+    let policy = IdPolicy::from_args(&args)?;
+    let duplicates = DuplicatePolicy::from_args(&args)?;
+    let scoring = ScoringRule::from_args(&args)?;
+    let strict = match args.iter().any(|arg| arg == "--strict") {
+        true => Some(StrictOptions::from_args(&args)?),
+        false => None,
+    };
+    let file_path = args.iter().find(|arg| !arg.starts_with("--"))
+        .map_or("input.txt", |arg| arg.as_str());
+    let cards = read_cards(file_path, strict.as_ref())?;
+    println!("Total points: {}", score_cards(&cards, policy, duplicates, &scoring)?);
 
     Ok(())
 }
 
-fn score_input(input: &str) -> Option<usize> {
-    let cards = parse(input)?;
-    Some(cards.iter().map(|card| score_card(card)).sum())
-}
-
-fn parse(input: &str) -> Option<Vec<Card>> {
-    input.lines().map(|l| {
-        let (_, nums_part) = l.split_once(":")?;
-        let (winning_nums_part, our_nums_part) = nums_part.split_once("|")?;
-        let winning_nums = parse_nums(winning_nums_part)?;
-        let our_nums = parse_nums(our_nums_part)?;
-        Some(Card {winning_nums, our_nums})
-    }).try_fold(Vec::<Card>::new(), |mut v, opt_card| {
-        opt_card.map(|card| {
-            v.push(card);
-            v
-        })
+fn read_cards(file_path: &str, strict: Option<&StrictOptions>) -> Result<Vec<Card>, String> {
+    let mut file = File::open(file_path).map_err(|err| err.to_string())?;
+    let mut input = String::new();
+    file.read_to_string(&mut input).map_err(|err| err.to_string())?;
+    let Some(options) = strict else {
+        return parse(&input);
+    };
+    parse_strict(&input, options).map_err(|problems| {
+        for problem in &problems {
+            eprintln!("{}", problem);
+        }
+        format!("Found {} problem(s) in strict mode!", problems.len())
     })
 }
 
-fn parse_nums(s: &str) -> Option<Vec<usize>> {
-    s.split(" ").filter(|s| *s != "").map(|s| s.trim().parse::<usize>())
-        .try_fold(Vec::<usize>::new(), |mut v, num_res| {
-            num_res.map_or(None, |num| {
-                v.push(num);
-                Some(v)
-            })
-        })
+fn score_cards(
+    cards: &[Card],
+    policy: IdPolicy,
+    duplicates: DuplicatePolicy,
+    scoring: &ScoringRule,
+) -> Result<usize, String> {
+    validate_ids(cards, policy)?;
+    cards.iter().try_fold(0usize, |total, card| {
+        total.checked_add(score_card(card, duplicates, scoring)?)
+            .ok_or("Total points overflowed!".to_string())
+    })
 }
 
-fn score_card(card: &Card) -> usize {
-    let mut points = 0;
-    for our_num in &card.our_nums {
-        for winning_num in &card.winning_nums {
-            if our_num == winning_num {
-                points = if points == 0 {
-                    1
-                } else {
-                    points << 1
-                };
-                break;
-            }
-        }
-    }
-    points
+fn score_card(card: &Card, duplicates: DuplicatePolicy, scoring: &ScoringRule) -> Result<usize, String> {
+    let matches = count_matches(card, duplicates)?;
+    scoring.score(matches)
+        .ok_or(format!("Card {}: Could not score {} matches with the {} rule!", card.id, matches, scoring.name()))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{score_input};
+    use scratchcards::{card::{parse, IdPolicy}, matching::DuplicatePolicy, scoring::ScoringRule};
+
+    use crate::score_cards;
 
     #[test]
-    fn test_score_input() {
-        let input = [
+    fn test_score_cards() {
+        let cards = parse(&[
             "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
             "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
             "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
             "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
             "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
             "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-        ].join("\n");
+        ].join("\n")).unwrap();
+        assert_eq!(
+            score_cards(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct, &ScoringRule::Doubling),
+            Ok(13),
+        );
+    }
+
+    #[test]
+    fn test_score_cards_validates_ids() {
+        let cards = parse("Card 2: 1 2 | 1 2\nCard 1: 3 | 3").unwrap();
+        assert_eq!(
+            score_cards(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct, &ScoringRule::Doubling),
+            Err("Line 1: Expected card 1, found card 2!".to_string()),
+        );
+        assert_eq!(score_cards(&cards, IdPolicy::Unique, DuplicatePolicy::Distinct, &ScoringRule::Doubling), Ok(3));
+    }
+
+    #[test]
+    fn test_score_cards_with_duplicates() {
+        let cards = parse("Card 1: 5 5 7 | 5 7 7 9").unwrap();
+        let score = |duplicates| score_cards(&cards, IdPolicy::Sequential, duplicates, &ScoringRule::Doubling);
+        assert_eq!(score(DuplicatePolicy::Distinct), Ok(2));
+        assert_eq!(score(DuplicatePolicy::CountOurs), Ok(4));
+        assert_eq!(score(DuplicatePolicy::CountPairs), Ok(8));
+        assert!(score(DuplicatePolicy::Reject).is_err());
+    }
+
+    #[test]
+    fn test_score_cards_with_many_pairs() {
+        // 13 winning and 5 of our numbers are all equal, so they form 65 pairs.
+        let cards = parse(&format!("Card 1: {}| {}", "7 ".repeat(13), "7 ".repeat(5))).unwrap();
+        assert_eq!(
+            score_cards(&cards, IdPolicy::Sequential, DuplicatePolicy::CountPairs, &ScoringRule::Doubling),
+            Err("Card 1: Could not score 65 matches with the doubling rule!".to_string()),
+        );
+        assert_eq!(
+            score_cards(&cards, IdPolicy::Sequential, DuplicatePolicy::CountPairs, &ScoringRule::Linear),
+            Ok(65),
+        );
+    }
+
+    #[test]
+    fn test_score_cards_with_scoring_rules() {
+        let cards = parse(&[
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+        ].join("\n")).unwrap();
+        let score = |scoring: ScoringRule| {
+            score_cards(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct, &scoring)
+        };
+        assert_eq!(score(ScoringRule::Linear), Ok(8));
+        assert_eq!(score(ScoringRule::Fibonacci), Ok(9));
+        assert_eq!(score(ScoringRule::Table(vec![0, 1, 10, 100, 1000])), Ok(1020));
         assert_eq!(
-            score_input(&input),
-            Some(13),
+            score(ScoringRule::Table(vec![0, 1])),
+            Err("Card 1: Could not score 4 matches with the table rule!".to_string()),
         );
     }
 }
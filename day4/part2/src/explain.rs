@@ -0,0 +1,129 @@
+use scratchcards::{
+    card::Card, count::{count_copies, won_cards, Counter}, matching::{matches_by_id, DuplicatePolicy},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct CardRow<C> {
+    pub id: usize,
+    pub matches: usize,
+    // Ids of the cards that won copies of this card, with how many copies each.
+    pub received: Vec<(usize, C)>,
+    pub copies: C,
+}
+
+// Rows are in id order, which is the order the cascade runs in.
+pub fn explain<C: Counter>(cards: &[Card], duplicates: DuplicatePolicy) -> Result<Vec<CardRow<C>>, String> {
+    let matches = matches_by_id(cards, duplicates)?;
+    let (copies, _) = count_copies::<C>(&matches)?;
+    // A card's copies are final before it wins anything, so they are what it hands on.
+    let mut received = vec![Vec::new(); matches.len()];
+    for (card_idx, won_idxs) in won_cards(&matches).into_iter().enumerate() {
+        for won_idx in won_idxs {
+            received[won_idx].push((matches[card_idx].0, copies[card_idx].clone()));
+        }
+    }
+    Ok(matches.into_iter().zip(copies).zip(received)
+        .map(|(((id, matches), copies), received)| CardRow {id, matches, received, copies})
+        .collect())
+}
+
+pub fn format_rows<C: Counter>(rows: &[CardRow<C>], format: Option<&str>) -> Result<String, String> {
+    match format {
+        None | Some("table") => Ok(to_table(rows)),
+        Some("dot") => Ok(to_dot(rows)),
+        Some(format) => Err(format!("Unknown explain format '{}'!", format)),
+    }
+}
+
+pub fn to_table<C: Counter>(rows: &[CardRow<C>]) -> String {
+    let mut lines = vec![format!("{:>6} {:>8} {:>8}  Received from", "Card", "Matches", "Copies")];
+    for row in rows {
+        let received: Vec<String> = row.received.iter()
+            .map(|(from_id, copies)| format!("{} ({})", from_id, copies))
+            .collect();
+        lines.push(format!(
+            "{:>6} {:>8} {:>8}  {}",
+            row.id, row.matches, row.copies,
+            if received.is_empty() {"-".to_string()} else {received.join(", ")},
+        ));
+    }
+    let mut total = C::zero();
+    let fits = rows.iter().all(|row| total.try_add(&row.copies));
+    lines.push(format!("Total: {}", if fits {total.to_string()} else {"overflow".to_string()}));
+    lines.join("\n")
+}
+
+pub fn to_dot<C: Counter>(rows: &[CardRow<C>]) -> String {
+    let mut lines = vec!["digraph cascade {".to_string()];
+    for row in rows {
+        lines.push(format!(
+            "    c{} [label=\"Card {}\\n{} matches\\n{} copies\", shape=box];",
+            row.id, row.id, row.matches, row.copies,
+        ));
+    }
+    for row in rows {
+        for (from_id, copies) in &row.received {
+            lines.push(format!("    c{} -> c{} [label=\"{}\"];", from_id, row.id, copies));
+        }
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use scratchcards::{card::{parse, IdPolicy}, count::BigCount, matching::DuplicatePolicy};
+
+    use crate::{count_total_cards, explain::{explain, format_rows, to_dot, to_table, CardRow}};
+
+    #[test]
+    fn test_explain() {
+        let cards = parse(&[
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ].join("\n")).unwrap();
+        let rows = explain::<usize>(&cards, DuplicatePolicy::Distinct).unwrap();
+        assert_eq!(
+            to_table(&rows),
+            [
+                "  Card  Matches   Copies  Received from",
+                "     1        4        1  -",
+                "     2        2        2  1 (1)",
+                "     3        2        4  1 (1), 2 (2)",
+                "     4        1        8  1 (1), 2 (2), 3 (4)",
+                "     5        0       14  1 (1), 3 (4), 4 (8)",
+                "     6        0        1  -",
+                "Total: 30",
+            ].join("\n"),
+        );
+        let dot = to_dot(&rows[..2]);
+        assert_eq!(
+            dot,
+            [
+                "digraph cascade {",
+                "    c1 [label=\"Card 1\\n4 matches\\n1 copies\", shape=box];",
+                "    c2 [label=\"Card 2\\n2 matches\\n2 copies\", shape=box];",
+                "    c1 -> c2 [label=\"1\"];",
+                "}",
+            ].join("\n"),
+        );
+    }
+
+    #[test]
+    fn test_explain_big() {
+        let cards = parse(include_str!("../overflow.txt")).unwrap();
+        assert_eq!(
+            explain::<usize>(&cards, DuplicatePolicy::Distinct),
+            Err("Card 66: Number of cards overflowed, use --big for arbitrary precision!".to_string()),
+        );
+        let rows: Vec<CardRow<BigCount>> = explain(&cards, DuplicatePolicy::Distinct).unwrap();
+        assert_eq!(rows.len(), cards.len());
+        let total = count_total_cards::<BigCount>(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct).unwrap();
+        assert!(format_rows(&rows, None).unwrap().ends_with(&format!("Total: {}", total)));
+        assert!(format_rows(&rows, Some("svg")).is_err());
+    }
+}
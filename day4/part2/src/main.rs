@@ -1,94 +1,325 @@
-use std::{fs::File, io::Read};
+use std::{env, fs::File, io::{self, BufRead, BufReader, Read}};
 
-struct Card {
-    copies: usize,
-    winning_nums: Vec<usize>,
-    our_nums: Vec<usize>,
-}
+use common::args::{check_flags, flag_value, parse_flag};
+use scratchcards::{
+    card::{parse, validate_ids, Card, IdPolicy, ID_FLAGS}, count::{count_copies, BigCount, Counter},
+    expected::{empirical, expected}, generate::{generate, GeneratorConfig, GENERATOR_FLAGS},
+    matching::{matches_by_id, DuplicatePolicy, DUPLICATE_FLAGS}, scoring::{ScoringRule, SCORING_FLAGS},
+    stream::StreamingCounter, strict::{parse_strict, StrictOptions, StrictParser, STRICT_FLAGS},
+};
+
+mod explain;
+mod report;
+
+const DEFAULT_SAMPLES: usize = 1000;
 
 fn main() -> Result<(), String> {
-    let mut file = File::open("input.txt").map_err(|err| err.to_string())?;
-    let mut input = String::new();
-    let _ = file.read_to_string(&mut input);
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "generate") {
+        return run_generate(&args[1..]);
+    }
+    if args.first().is_some_and(|arg| arg == "expected") {
+        return run_expected(&args[1..]);
+    }
+    check_flags(&args, &[ID_FLAGS, DUPLICATE_FLAGS, STRICT_FLAGS, &["--big", "--format="]])?;
+    let policy = IdPolicy::from_args(&args)?;
+    let duplicates = DuplicatePolicy::from_args(&args)?;
+    let strict = match args.iter().any(|arg| arg == "--strict") {
+        true => Some(StrictOptions::from_args(&args)?),
+        false => None,
+    };
+    let mut positional_args = args.iter().filter(|arg| !arg.starts_with("--"));
+    let first_arg = positional_args.next().map_or("input.txt", |arg| arg.as_str());
+    if first_arg == "explain" {
+        let file_path = positional_args.next().map_or("input.txt", |arg| arg.as_str());
+        let cards = read_cards(file_path, strict.as_ref())?;
+        validate_ids(&cards, policy)?;
+        let format = flag_value(&args, "--format")?;
+        if args.iter().any(|arg| arg == "--big") {
+            println!("{}", explain::format_rows(&explain::explain::<BigCount>(&cards, duplicates)?, format)?);
+        } else {
+            println!("{}", explain::format_rows(&explain::explain::<usize>(&cards, duplicates)?, format)?);
+        }
+        return Ok(());
+    }
+
+    if first_arg == "report" {
+        let file_path = positional_args.next().map_or("input.txt", |arg| arg.as_str());
+        let cards = read_cards(file_path, strict.as_ref())?;
+        validate_ids(&cards, policy)?;
+        if args.iter().any(|arg| arg == "--big") {
+            println!("{}", report::report::<BigCount>(&cards, duplicates)?);
+        } else {
+            println!("{}", report::report::<usize>(&cards, duplicates)?);
+        }
+        return Ok(());
+    }
+
+    if first_arg == "stream" {
+        // Reads from stdin unless a file is given, cards must be in id order.
+        let reader: Box<dyn Read> = match positional_args.next() {
+            Some(file_path) if file_path != "-" => {
+                Box::new(File::open(file_path).map_err(|err| err.to_string())?)
+            }
+            _ => Box::new(io::stdin()),
+        };
+        let lines = BufReader::new(reader).lines().map(|line| line.map_err(|err| err.to_string()));
+        let strict_parser = strict.map(StrictParser::new);
+        if args.iter().any(|arg| arg == "--big") {
+            let total = stream_total_cards::<BigCount>(lines, policy, duplicates, strict_parser)?;
+            println!("Total number of cards: {}", total);
+        } else {
+            let total = stream_total_cards::<usize>(lines, policy, duplicates, strict_parser)?;
+            println!("Total number of cards: {}", total);
+        }
+        return Ok(());
+    }
+
+    let cards = read_cards(first_arg, strict.as_ref())?;
+    // Counts beyond 64 bits need `--big`.
+    if args.iter().any(|arg| arg == "--big") {
+        println!("Total number of cards: {}", count_total_cards::<BigCount>(&cards, policy, duplicates)?);
+    } else {
+        println!("Total number of cards: {}", count_total_cards::<usize>(&cards, policy, duplicates)?);
+    }
+
+    Ok(())
+}
+
+fn run_generate(args: &[String]) -> Result<(), String> {
+    check_flags(args, &[GENERATOR_FLAGS])?;
+    for line in generate(&GeneratorConfig::from_args(args, &GeneratorConfig::default())?)? {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn run_expected(args: &[String]) -> Result<(), String> {
+    check_flags(args, &[GENERATOR_FLAGS, SCORING_FLAGS, &["--samples="]])?;
+    // Numbers are drawn uniformly unless `--match-rate=` is given.
+    let config = GeneratorConfig::from_args(args, &GeneratorConfig {match_rate: None, ..GeneratorConfig::default()})?;
+    let samples = parse_flag(args, "--samples", DEFAULT_SAMPLES)?;
+    let scoring = ScoringRule::from_args(args)?;
+    let exact = expected(&config, &scoring)?;
+    let sampled = empirical(&config, &scoring, samples)?;
+
+    println!("{:>24} {:>14} {:>14}", "", "Exact", "Empirical");
+    for (matches, (exact_prob, sampled_prob)) in exact.match_probs.iter().zip(&sampled.match_probs).enumerate() {
+        println!("{:>24} {:>14.6} {:>14.6}", format!("P({} matches)", matches), exact_prob, sampled_prob);
+    }
     println!(
-        "Total number of cards: {}", 
-        count_total_cards(&input).expect("Failed to parse input!")
+        "{:>24} {:>14.4} {:>14.4}",
+        format!("{} score per card", scoring.name()), exact.score_per_card, sampled.score_per_card,
     );
-
+    println!("{:>24} {:>14.4e} {:>14.4e}", "Total cards", exact.total_cards, sampled.total_cards);
+    println!("Empirical values are averaged over {} generated inputs.", samples);
     Ok(())
 }
 
-fn count_total_cards(input: &str) -> Option<usize> {
-    let mut count = 0;
-    let mut cards = parse(input)?;
-    for i in 0..cards.len() {
-        let card = cards.get(i)?;
-        let copies = card.copies;
-        count += copies;
-        let winning_nums_count = count_winning_nums(&card);
-        for j in i+1..i+winning_nums_count+1 {
-            if let Some(future_card) = cards.get_mut(j) {
-                future_card.copies += copies;
-            }
-        }
+fn read_cards(file_path: &str, strict: Option<&StrictOptions>) -> Result<Vec<Card>, String> {
+    let mut file = File::open(file_path).map_err(|err| err.to_string())?;
+    let mut input = String::new();
+    file.read_to_string(&mut input).map_err(|err| err.to_string())?;
+    match strict {
+        None => parse(&input),
+        Some(options) => parse_strict(&input, options).map_err(strict_error),
     }
-    return Some(count)
 }
 
-fn parse(input: &str) -> Option<Vec<Card>> {
-    input.lines().map(|l| {
-        let (_, nums_part) = l.split_once(":")?;
-        let (winning_nums_part, our_nums_part) = nums_part.split_once("|")?;
-        let winning_nums = parse_nums(winning_nums_part)?;
-        let our_nums = parse_nums(our_nums_part)?;
-        Some(Card {copies: 1, winning_nums, our_nums})
-    }).try_fold(Vec::<Card>::new(), |mut v, opt_card| {
-        opt_card.map(|card| {
-            v.push(card);
-            v
-        })
-    })
+fn strict_error(problems: Vec<String>) -> String {
+    for problem in &problems {
+        eprintln!("{}", problem);
+    }
+    format!("Found {} problem(s) in strict mode!", problems.len())
 }
 
-fn parse_nums(s: &str) -> Option<Vec<usize>> {
-    s.split(" ").filter(|s| *s != "").map(|s| s.trim().parse::<usize>())
-        .try_fold(Vec::<usize>::new(), |mut v, num_res| {
-            num_res.map_or(None, |num| {
-                v.push(num);
-                Some(v)
-            })
-        })
+fn count_total_cards<C: Counter>(
+    cards: &[Card],
+    policy: IdPolicy,
+    duplicates: DuplicatePolicy,
+) -> Result<C, String> {
+    validate_ids(cards, policy)?;
+    let (_, total) = count_copies::<C>(&matches_by_id(cards, duplicates)?)?;
+    Ok(total)
 }
 
-fn count_winning_nums(card: &Card) -> usize {
-    let mut count = 0;
-    for our_num in &card.our_nums {
-        for winning_num in &card.winning_nums {
-            if our_num == winning_num {
-                count += 1;
-            }
+// Strict mode stops at the first line with problems.
+fn stream_total_cards<C: Counter>(
+    lines: impl Iterator<Item = Result<String, String>>,
+    policy: IdPolicy,
+    duplicates: DuplicatePolicy,
+    mut strict_parser: Option<StrictParser>,
+) -> Result<C, String> {
+    let mut counter = StreamingCounter::<C>::new(policy, duplicates)?;
+    for line in lines {
+        let line = line?;
+        match strict_parser.as_mut() {
+            None => counter.push_line(&line)?,
+            Some(parser) => counter.push_card(&parser.parse_line(&line).map_err(strict_error)?)?,
         }
     }
-    count
+    Ok(counter.total().clone())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{count_total_cards};
+    use std::collections::HashMap;
+
+    use common::rng::Rng;
+    use scratchcards::{
+        card::{parse, Card, IdPolicy}, count::BigCount, generate::{generate, GeneratorConfig},
+        matching::{count_matches, DuplicatePolicy}, strict::{StrictOptions, StrictParser},
+    };
+
+    use crate::{count_total_cards, stream_total_cards};
 
     #[test]
-    fn test_score_input() {
-        let input = [
+    fn test_count_total_cards() {
+        let cards = parse(&[
             "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
             "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
             "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
             "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
             "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
             "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-        ].join("\n");
+        ].join("\n")).unwrap();
+        assert_eq!(count_total_cards::<usize>(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct), Ok(30));
+    }
+
+    #[test]
+    fn test_count_total_cards_keyed_by_id() {
+        let shuffled = parse(&[
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+        ].join("\n")).unwrap();
+        assert_eq!(
+            count_total_cards::<usize>(&shuffled, IdPolicy::Sequential, DuplicatePolicy::Distinct),
+            Err("Line 1: Expected card 1, found card 3!".to_string()),
+        );
+        assert_eq!(count_total_cards::<usize>(&shuffled, IdPolicy::Unique, DuplicatePolicy::Distinct), Ok(30));
+        // Card 1 wins copies of cards 2 to 5, but only card 4 exists.
+        let gapped = parse("Card 1: 1 2 3 4 | 1 2 3 4\nCard 4: 5 | 6").unwrap();
+        assert_eq!(count_total_cards::<usize>(&gapped, IdPolicy::Unique, DuplicatePolicy::Distinct), Ok(3));
+    }
+
+    #[test]
+    fn test_count_total_cards_with_duplicates() {
+        let cards = parse("Card 1: 5 5 | 5 5\nCard 2: 1 | 2\nCard 3: 1 | 2\nCard 4: 1 | 2\nCard 5: 1 | 2").unwrap();
+        let count = |duplicates| count_total_cards::<usize>(&cards, IdPolicy::Sequential, duplicates);
+        assert_eq!(count(DuplicatePolicy::Distinct), Ok(6));
+        assert_eq!(count(DuplicatePolicy::CountOurs), Ok(7));
+        assert_eq!(count(DuplicatePolicy::CountPairs), Ok(9));
+        assert!(count(DuplicatePolicy::Reject).is_err());
+    }
+
+    #[test]
+    fn test_count_total_cards_overflow() {
+        let cards = parse(include_str!("../overflow.txt")).unwrap();
+        assert_eq!(
+            count_total_cards::<usize>(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct),
+            Err("Card 66: Number of cards overflowed, use --big for arbitrary precision!".to_string()),
+        );
+        let mut copies = [1u128; 100];
+        for idx in 0..copies.len() {
+            for won_idx in idx + 1..copies.len().min(idx + 6) {
+                copies[won_idx] += copies[idx];
+            }
+        }
+        assert_eq!(
+            count_total_cards::<BigCount>(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct)
+                .map(|count| count.to_string()),
+            Ok(copies.iter().sum::<u128>().to_string()),
+        );
+    }
+
+    #[test]
+    fn test_stream_total_cards_matches_count_total_cards() {
+        let (policy, duplicates) = (IdPolicy::Sequential, DuplicatePolicy::Distinct);
+        let input = include_str!("../overflow.txt");
+        let lines = || input.lines().map(|l| Ok(l.to_string()));
+        assert_eq!(
+            stream_total_cards::<BigCount>(lines(), policy, duplicates, None),
+            count_total_cards::<BigCount>(&parse(input).unwrap(), policy, duplicates),
+        );
+        assert_eq!(
+            stream_total_cards::<usize>(lines(), policy, duplicates, None),
+            Err("Card 66: Number of cards overflowed, use --big for arbitrary precision!".to_string()),
+        );
+        let input = include_str!("../input.txt");
+        let lines = input.lines().map(|l| Ok(l.to_string()));
+        assert_eq!(
+            stream_total_cards::<usize>(lines, policy, duplicates, None),
+            count_total_cards::<usize>(&parse(input).unwrap(), policy, duplicates),
+        );
+    }
+
+    // Plays every single card instance one after another.
+    fn brute_force_total_cards(cards: &[Card]) -> usize {
+        let cards_by_id: HashMap<usize, &Card> = cards.iter().map(|card| (card.id, card)).collect();
+        let mut pending: Vec<usize> = cards.iter().map(|card| card.id).collect();
+        let mut count = 0;
+        while let Some(id) = pending.pop() {
+            count += 1;
+            let matches = count_matches(cards_by_id[&id], DuplicatePolicy::Distinct).unwrap();
+            pending.extend((id + 1..=id + matches).filter(|won_id| cards_by_id.contains_key(won_id)));
+        }
+        count
+    }
+
+    #[test]
+    fn test_count_total_cards_matches_brute_force() {
+        let mut rng = Rng::new(49);
+        for _ in 0..200 {
+            let config = GeneratorConfig {
+                cards: rng.range_inclusive(1, 12),
+                winning: rng.range_inclusive(1, 5),
+                ours: rng.range_inclusive(1, 6),
+                match_rate: if rng.below(4) == 0 {None} else {Some(rng.next_f64())},
+                max_num: 20,
+                seed: rng.next_u64(),
+            };
+            let lines = generate(&config).unwrap();
+            let input = lines.join("\n");
+            let cards = parse(&input).unwrap();
+            let expected = brute_force_total_cards(&cards);
+            assert_eq!(
+                count_total_cards::<usize>(&cards, IdPolicy::Sequential, DuplicatePolicy::Distinct),
+                Ok(expected),
+                "{}", input,
+            );
+            let lines = lines.into_iter().map(Ok);
+            let total = stream_total_cards::<usize>(lines, IdPolicy::Sequential, DuplicatePolicy::Distinct, None);
+            assert_eq!(total, Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_stream_total_cards_read_error() {
+        let lines = vec![Ok("Card 1: 1 | 1".to_string()), Err("stream did not contain valid UTF-8".to_string())];
+        assert_eq!(
+            stream_total_cards::<usize>(lines.into_iter(), IdPolicy::Sequential, DuplicatePolicy::Distinct, None),
+            Err("stream did not contain valid UTF-8".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_stream_total_cards_strict() {
+        let lines = || ["Card 1: 1 | 1", "Card 2: 2 | 3", "Card 3: 1 | 2 3"].map(|l| Ok(l.to_string())).into_iter();
+        let stream = |strict_parser| {
+            stream_total_cards::<usize>(lines(), IdPolicy::Sequential, DuplicatePolicy::Distinct, strict_parser)
+        };
+        assert_eq!(stream(None), Ok(4));
+        assert_eq!(
+            stream(Some(StrictParser::new(StrictOptions::default()))),
+            Err("Found 1 problem(s) in strict mode!".to_string()),
+        );
         assert_eq!(
-            count_total_cards(&input),
-            Some(30),
+            stream_total_cards::<usize>(lines(), IdPolicy::Unique, DuplicatePolicy::Distinct, None),
+            Err("Streaming needs cards in id order, use --ids=sequential!".to_string()),
         );
     }
 }
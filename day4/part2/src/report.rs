@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use common::histogram::bar_line;
+use scratchcards::{
+    card::Card, count::{count_copies, count_descendants, won_cards, Counter},
+    matching::{matched_nums, matches_by_id, DuplicatePolicy},
+};
+
+const TOP_WINNING_NUMS: usize = 10;
+
+pub fn report<C: Counter>(cards: &[Card], duplicates: DuplicatePolicy) -> Result<String, String> {
+    let matches = matches_by_id(cards, duplicates)?;
+    let mut lines = vec![format!("Cards: {}", cards.len())];
+
+    lines.push(String::new());
+    lines.push("Matches per card:".to_string());
+    let max_matches = matches.iter().map(|(_, card_matches)| *card_matches).max().unwrap_or(0);
+    let mut match_counts = vec![0; max_matches + 1];
+    for (_, card_matches) in &matches {
+        match_counts[*card_matches] += 1;
+    }
+    let max_count = match_counts.iter().copied().max().unwrap_or(0);
+    let label_width = max_matches.to_string().len();
+    for (card_matches, count) in match_counts.iter().enumerate() {
+        lines.push(format!("  {}", bar_line(&card_matches.to_string(), *count, max_count, label_width)));
+    }
+
+    lines.push(String::new());
+    lines.push("Most hit winning numbers:".to_string());
+    for (num, count) in hit_counts(cards, duplicates)?.into_iter().take(TOP_WINNING_NUMS) {
+        lines.push(format!("  {}: {} hits", num, count));
+    }
+
+    let (_, total) = count_copies::<C>(&matches)?;
+    let descendants = count_descendants::<C>(&matches)?;
+    lines.push(String::new());
+    // Ties go to the lower id.
+    let most_idx = (0..descendants.len())
+        .reduce(|most_idx, card_idx| if descendants[card_idx] > descendants[most_idx] {card_idx} else {most_idx});
+    if let Some(card_idx) = most_idx {
+        // The copies downstream of a card are the descendants of the cards it wins.
+        let mut copies = C::zero();
+        for won_idx in won_cards(&matches)[card_idx].clone() {
+            copies.try_add(&descendants[won_idx]);
+        }
+        lines.push(format!("Most downstream copies: card {} with {} copies", matches[card_idx].0, copies));
+    }
+    lines.push(String::new());
+    lines.push(format!("Share of the {} total cards per original card:", total));
+    for ((id, _), count) in matches.iter().zip(&descendants) {
+        lines.push(format!(
+            "  card {}: {} ({:.2}%)", id, count, count.to_f64() * 100.0 / total.to_f64(),
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+// Winning numbers with how often they are matched under the duplicate policy, most hit first.
+fn hit_counts(cards: &[Card], duplicates: DuplicatePolicy) -> Result<Vec<(usize, usize)>, String> {
+    let mut counts = HashMap::<usize, usize>::new();
+    for card in cards {
+        for num in matched_nums(card, duplicates)? {
+            *counts.entry(num).or_insert(0) += 1;
+        }
+    }
+    let mut counts: Vec<(usize, usize)> = counts.into_iter().collect();
+    counts.sort_by(|(num_a, count_a), (num_b, count_b)| count_b.cmp(count_a).then(num_a.cmp(num_b)));
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use scratchcards::{card::parse, count::BigCount, matching::DuplicatePolicy};
+
+    use crate::report::{hit_counts, report};
+
+    #[test]
+    fn test_report() {
+        let cards = parse(&[
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ].join("\n")).unwrap();
+        assert_eq!(&hit_counts(&cards, DuplicatePolicy::Distinct).unwrap()[..3], &[(1, 1), (17, 1), (21, 1)]);
+        assert_eq!(
+            report::<usize>(&cards, DuplicatePolicy::Distinct),
+            Ok([
+                "Cards: 6",
+                "",
+                "Matches per card:",
+                "  0 | ######################################## 2",
+                "  1 | #################### 1",
+                "  2 | ######################################## 2",
+                "  3 |  0",
+                "  4 | #################### 1",
+                "",
+                "Most hit winning numbers:",
+                "  1: 1 hits",
+                "  17: 1 hits",
+                "  21: 1 hits",
+                "  32: 1 hits",
+                "  48: 1 hits",
+                "  61: 1 hits",
+                "  83: 1 hits",
+                "  84: 1 hits",
+                "  86: 1 hits",
+                "",
+                "Most downstream copies: card 1 with 14 copies",
+                "",
+                "Share of the 30 total cards per original card:",
+                "  card 1: 15 (50.00%)",
+                "  card 2: 7 (23.33%)",
+                "  card 3: 4 (13.33%)",
+                "  card 4: 2 (6.67%)",
+                "  card 5: 1 (3.33%)",
+                "  card 6: 1 (3.33%)",
+            ].join("\n")),
+        );
+    }
+
+    #[test]
+    fn test_hit_counts_with_duplicates() {
+        let cards = parse("Card 1: 5 5 7 | 5 7 7 9\nCard 2: 7 | 7").unwrap();
+        assert_eq!(hit_counts(&cards, DuplicatePolicy::Distinct), Ok(vec![(7, 2), (5, 1)]));
+        assert_eq!(hit_counts(&cards, DuplicatePolicy::CountPairs), Ok(vec![(7, 3), (5, 2)]));
+        assert!(hit_counts(&cards, DuplicatePolicy::Reject).is_err());
+    }
+
+    #[test]
+    fn test_report_big() {
+        let cards = parse(include_str!("../overflow.txt")).unwrap();
+        assert_eq!(
+            report::<usize>(&cards, DuplicatePolicy::Distinct),
+            Err("Card 66: Number of cards overflowed, use --big for arbitrary precision!".to_string()),
+        );
+        let big_report = report::<BigCount>(&cards, DuplicatePolicy::Distinct).unwrap();
+        assert!(big_report.contains("Most downstream copies: card 1 with "));
+        let cards = parse("Card 1: 1 | 1\nCard 2: 2 | 3").unwrap();
+        let big_report = report::<BigCount>(&cards, DuplicatePolicy::Distinct);
+        assert_eq!(big_report, report::<usize>(&cards, DuplicatePolicy::Distinct));
+    }
+}
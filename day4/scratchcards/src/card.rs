@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use common::args::flag_value;
+
+pub const ID_FLAGS: &[&str] = &["--ids="];
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Card {
+    pub id: usize,
+    pub winning_nums: Vec<usize>,
+    pub our_nums: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum IdPolicy {
+    // Ids must be 1, 2, 3, ... in file order.
+    Sequential,
+    // Ids only have to be unique, cards may be out of order and ids may be missing.
+    Unique,
+}
+
+impl IdPolicy {
+    pub fn from_args(args: &[String]) -> Result<IdPolicy, String> {
+        match flag_value(args, "--ids")? {
+            None | Some("sequential") => Ok(IdPolicy::Sequential),
+            Some("unique") => Ok(IdPolicy::Unique),
+            Some(value) => Err(format!("Unknown card id policy '{}'!", value)),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Card>, String> {
+    input.lines().enumerate()
+        .map(|(line_idx, line)| {
+            parse_card(line).ok_or(format!("Line {}: Could not parse card '{}'!", line_idx + 1, line))
+        })
+        .collect()
+}
+
+pub fn parse_card(line: &str) -> Option<Card> {
+    let (id_part, nums_part) = line.split_once(":")?;
+    let id = id_part.strip_prefix("Card")?.trim().parse::<usize>().ok()?;
+    let (winning_nums_part, our_nums_part) = nums_part.split_once("|")?;
+    let winning_nums = parse_nums(winning_nums_part)?;
+    let our_nums = parse_nums(our_nums_part)?;
+    Some(Card {id, winning_nums, our_nums})
+}
+
+fn parse_nums(s: &str) -> Option<Vec<usize>> {
+    s.split(" ").filter(|s| !s.is_empty()).map(|s| s.trim().parse::<usize>().ok()).collect()
+}
+
+// Cards are expected one per line, so a card's index is its line number minus one.
+pub fn validate_ids(cards: &[Card], policy: IdPolicy) -> Result<(), String> {
+    let mut card_idxs = HashMap::<usize, usize>::new();
+    for (card_idx, card) in cards.iter().enumerate() {
+        if let Some(first_idx) = card_idxs.insert(card.id, card_idx) {
+            return Err(format!(
+                "Line {}: Card {} was already defined on line {}!", card_idx + 1, card.id, first_idx + 1,
+            ));
+        }
+        if policy == IdPolicy::Sequential && card.id != card_idx + 1 {
+            return Err(format!(
+                "Line {}: Expected card {}, found card {}!", card_idx + 1, card_idx + 1, card.id,
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::{parse, validate_ids, Card, IdPolicy};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("Card   7: 41 48 | 83  6 48\nCard 8: 1 | 2"),
+            Ok(vec![
+                Card {id: 7, winning_nums: vec![41, 48], our_nums: vec![83, 6, 48]},
+                Card {id: 8, winning_nums: vec![1], our_nums: vec![2]},
+            ]),
+        );
+        assert_eq!(
+            parse("Card 1: 1 | 2\nCard x: 1 | 2"),
+            Err("Line 2: Could not parse card 'Card x: 1 | 2'!".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_validate_ids() {
+        let cards = parse("Card 1: 1 | 2\nCard 3: 1 | 2\nCard 2: 1 | 2").unwrap();
+        assert_eq!(
+            validate_ids(&cards, IdPolicy::Sequential),
+            Err("Line 2: Expected card 2, found card 3!".to_string()),
+        );
+        assert_eq!(validate_ids(&cards, IdPolicy::Unique), Ok(()));
+        let cards = parse("Card 1: 1 | 2\nCard 5: 1 | 2\nCard 1: 1 | 2").unwrap();
+        assert_eq!(
+            validate_ids(&cards, IdPolicy::Unique),
+            Err("Line 3: Card 1 was already defined on line 1!".to_string()),
+        );
+    }
+}
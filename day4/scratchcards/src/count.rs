@@ -0,0 +1,248 @@
+use std::{cmp::Ordering, fmt, ops::Range};
+
+// Card counts grow exponentially with the number of cards, so the cascade
+// works on any counter that can report overflow.
+pub trait Counter: Clone + PartialOrd + fmt::Display {
+    fn zero() -> Self;
+    fn one() -> Self;
+    // `false` (leaving `self` unchanged) if the sum does not fit.
+    fn try_add(&mut self, other: &Self) -> bool;
+    // Nearest float, for shares and other ratios.
+    fn to_f64(&self) -> f64;
+}
+
+impl Counter for usize {
+    fn zero() -> usize {
+        0
+    }
+
+    fn one() -> usize {
+        1
+    }
+
+    fn try_add(&mut self, other: &usize) -> bool {
+        match self.checked_add(*other) {
+            Some(sum) => {
+                *self = sum;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+}
+
+// Only used for averages, `false` once the sum is no longer finite.
+impl Counter for f64 {
+    fn zero() -> f64 {
+        0.0
+    }
+
+    fn one() -> f64 {
+        1.0
+    }
+
+    fn try_add(&mut self, other: &f64) -> bool {
+        let sum = *self + other;
+        if !sum.is_finite() {
+            return false;
+        }
+        *self = sum;
+        true
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+}
+
+// Card `id` with `n` matches wins copies of cards `id + 1..=id + n` and ids
+// without a card win nothing. `cards` holds `(id, matches)` sorted by id, so
+// the cards won by a card are a range of card indices.
+pub fn won_cards(cards: &[(usize, usize)]) -> Vec<Range<usize>> {
+    cards.iter().enumerate()
+        .map(|(card_idx, (id, matches))| {
+            let last_won_id = id.saturating_add(*matches);
+            let won_count = cards[card_idx + 1..].partition_point(|(won_id, _)| *won_id <= last_won_id);
+            card_idx + 1..card_idx + 1 + won_count
+        })
+        .collect()
+}
+
+// Runs the cascade in id order, where every copy of a card wins one copy of
+// each card it wins. Returns the copies of every card and their total.
+pub fn count_copies<C: Counter>(cards: &[(usize, usize)]) -> Result<(Vec<C>, C), String> {
+    let mut copies = vec![C::one(); cards.len()];
+    let mut total = C::zero();
+    for (card_idx, won_idxs) in won_cards(cards).into_iter().enumerate() {
+        let card_copies = copies[card_idx].clone();
+        if !total.try_add(&card_copies) {
+            return Err(overflow_error(cards[card_idx].0));
+        }
+        for won_idx in won_idxs {
+            if !copies[won_idx].try_add(&card_copies) {
+                return Err(overflow_error(cards[won_idx].0));
+            }
+        }
+    }
+    Ok((copies, total))
+}
+
+// Every card instance descends from exactly one original card, so the number
+// of instances descending from each original (itself included) adds up to the
+// total. A card's descendants are itself plus the descendants of every card it wins.
+pub fn count_descendants<C: Counter>(cards: &[(usize, usize)]) -> Result<Vec<C>, String> {
+    let won = won_cards(cards);
+    let mut descendants = vec![C::one(); cards.len()];
+    for card_idx in (0..cards.len()).rev() {
+        for won_idx in won[card_idx].clone() {
+            let won_descendants = descendants[won_idx].clone();
+            if !descendants[card_idx].try_add(&won_descendants) {
+                return Err(overflow_error(cards[card_idx].0));
+            }
+        }
+    }
+    Ok(descendants)
+}
+
+pub fn overflow_error(card_id: usize) -> String {
+    format!("Card {}: Number of cards overflowed, use --big for arbitrary precision!", card_id)
+}
+
+// Arbitrary precision unsigned integer, only supports what the cascade needs.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BigCount {
+    // Little endian base 2^32 digits without trailing zeros.
+    limbs: Vec<u32>,
+}
+
+impl BigCount {
+    pub fn from_u64(value: u64) -> BigCount {
+        let mut limbs = vec![value as u32, (value >> 32) as u32];
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigCount {limbs}
+    }
+}
+
+impl Counter for BigCount {
+    fn zero() -> BigCount {
+        BigCount::from_u64(0)
+    }
+
+    fn one() -> BigCount {
+        BigCount::from_u64(1)
+    }
+
+    fn try_add(&mut self, other: &BigCount) -> bool {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry = 0u64;
+        for (idx, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(idx).copied().unwrap_or(0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        true
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.limbs.iter().rev().fold(0.0, |value, limb| value * 4294967296.0 + *limb as f64)
+    }
+}
+
+// Without trailing zeros, more limbs means a larger number.
+impl PartialOrd for BigCount {
+    fn partial_cmp(&self, other: &BigCount) -> Option<Ordering> {
+        let by_len = self.limbs.len().cmp(&other.limbs.len());
+        Some(by_len.then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev())))
+    }
+}
+
+impl fmt::Display for BigCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Repeatedly divides by 10^9, collecting decimal chunks from the lowest one.
+        let mut limbs = self.limbs.clone();
+        let mut chunks = Vec::<u32>::new();
+        while !limbs.is_empty() {
+            let mut remainder = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let value = (remainder << 32) | *limb as u64;
+                *limb = (value / 1_000_000_000) as u32;
+                remainder = value % 1_000_000_000;
+            }
+            chunks.push(remainder as u32);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((highest, rest)) => {
+                write!(f, "{}", highest)?;
+                for chunk in rest.iter().rev() {
+                    write!(f, "{:09}", chunk)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::count::{count_copies, count_descendants, won_cards, BigCount, Counter};
+
+    #[test]
+    fn test_count_copies() {
+        let cards = [(1, 4), (2, 2), (3, 2), (4, 1), (5, 0), (6, 0)];
+        assert_eq!(count_copies::<usize>(&cards), Ok((vec![1, 2, 4, 8, 14, 1], 30)));
+        // Card 2 is missing, so card 1 only wins a copy of card 3.
+        assert_eq!(won_cards(&[(1, 2), (3, 1), (7, 5)]), vec![1..2, 2..2, 3..3]);
+        assert_eq!(count_copies::<usize>(&[(1, 2), (3, 1), (7, 5)]), Ok((vec![1, 2, 1], 4)));
+        assert_eq!(count_descendants::<usize>(&cards), Ok(vec![15, 7, 4, 2, 1, 1]));
+    }
+
+    #[test]
+    fn test_usize_counter_overflow() {
+        let mut count = usize::MAX - 1;
+        assert!(count.try_add(&1));
+        assert!(!count.try_add(&1));
+        assert_eq!(count, usize::MAX);
+    }
+
+    #[test]
+    fn test_big_count() {
+        assert_eq!(BigCount::from_u64(0).to_string(), "0");
+        assert_eq!(BigCount::from_u64(1_000_000_007).to_string(), "1000000007");
+        let mut count = BigCount::from_u64(u64::MAX);
+        assert!(count.try_add(&BigCount::from_u64(u64::MAX)));
+        assert_eq!(count.to_string(), (u64::MAX as u128 * 2).to_string());
+        let mut expected = u64::MAX as u128 * 2;
+        for _ in 0..60 {
+            let other = count.clone();
+            assert!(count.try_add(&other));
+            expected *= 2;
+            assert_eq!(count.to_string(), expected.to_string());
+        }
+        // 2^128 no longer fits into a u128.
+        let mut count = BigCount::from_u64(1);
+        for _ in 0..128 {
+            let other = count.clone();
+            count.try_add(&other);
+        }
+        assert_eq!(count.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(count.to_f64(), 2f64.powi(128));
+        assert!(BigCount::from_u64(1 << 40) > BigCount::from_u64(u32::MAX as u64));
+        assert!(BigCount::from_u64(6) < BigCount::from_u64(7));
+        assert!(count > BigCount::from_u64(u64::MAX));
+    }
+}
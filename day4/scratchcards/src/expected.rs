@@ -0,0 +1,195 @@
+use crate::{
+    card::parse,
+    count::count_copies,
+    generate::{generate, GeneratorConfig},
+    matching::{matches_by_id, DuplicatePolicy},
+    scoring::ScoringRule,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Expectation {
+    // Probability of 0, 1, 2, ... matches on a single card.
+    pub match_probs: Vec<f64>,
+    pub score_per_card: f64,
+    pub total_cards: f64,
+}
+
+// Exact values for cards produced by `generate`. Uniform draws give the
+// hypergeometric distribution, otherwise the draws are followed one by one.
+pub fn expected(config: &GeneratorConfig, scoring: &ScoringRule) -> Result<Expectation, String> {
+    config.validate()?;
+    let match_probs = match config.match_rate {
+        None => hypergeometric(config.max_num, config.winning, config.ours),
+        Some(match_rate) => rated_match_probs(config, match_rate),
+    };
+    let score_per_card = expected_score(&match_probs, scoring)?;
+    let total_cards = expected_total_cards(&match_probs, config.cards);
+    Ok(Expectation {match_probs, score_per_card, total_cards})
+}
+
+// Averages over `samples` generated inputs, seeded `seed`, `seed + 1`, ...
+// Copies are counted as floats since the totals easily overflow.
+pub fn empirical(config: &GeneratorConfig, scoring: &ScoringRule, samples: usize) -> Result<Expectation, String> {
+    if samples == 0 {
+        return Err("Need at least one sample!".to_string());
+    }
+    if config.cards == 0 {
+        return Err("Need at least one card per sample!".to_string());
+    }
+    let mut match_counts = vec![0usize; config.winning.min(config.ours) + 1];
+    let mut score_sum = 0.0;
+    let mut total_sum = 0.0;
+    for sample in 0..samples {
+        let sample_config = GeneratorConfig {seed: config.seed.wrapping_add(sample as u64), ..config.clone()};
+        let cards = matches_by_id(&parse(&generate(&sample_config)?.join("\n"))?, DuplicatePolicy::Distinct)?;
+        for (_, matches) in &cards {
+            match_counts[*matches] += 1;
+            score_sum += score(scoring, *matches)?;
+        }
+        let (_, total) = count_copies::<f64>(&cards)
+            .map_err(|_err| format!("Sample {}: Number of cards does not fit a float!", sample))?;
+        total_sum += total;
+    }
+    let card_count = (samples * config.cards) as f64;
+    Ok(Expectation {
+        match_probs: match_counts.iter().map(|count| *count as f64 / card_count).collect(),
+        score_per_card: score_sum / card_count,
+        total_cards: total_sum / samples as f64,
+    })
+}
+
+// P(k) = C(winning, k) * C(max_num - winning, ours - k) / C(max_num, ours),
+// computed in log space to stay finite for large ranges.
+fn hypergeometric(max_num: usize, winning: usize, ours: usize) -> Vec<f64> {
+    (0..=winning.min(ours))
+        .map(|matches| {
+            if ours - matches > max_num - winning {
+                return 0.0;
+            }
+            (ln_binomial(winning, matches) + ln_binomial(max_num - winning, ours - matches)
+                - ln_binomial(max_num, ours)).exp()
+        })
+        .collect()
+}
+
+fn ln_binomial(n: usize, k: usize) -> f64 {
+    (1..=k).map(|i| ((n - k + i) as f64).ln() - (i as f64).ln()).sum()
+}
+
+// Mirrors `generate_card`: each draw hits an unmatched winning number with
+// `match_rate` unless one of the two kinds of numbers has run out.
+fn rated_match_probs(config: &GeneratorConfig, match_rate: f64) -> Vec<f64> {
+    let non_winning = config.max_num - config.winning;
+    let mut probs = vec![0.0; config.winning.min(config.ours) + 1];
+    probs[0] = 1.0;
+    for draw in 0..config.ours {
+        let mut next_probs = vec![0.0; probs.len()];
+        for (matches, prob) in probs.iter().enumerate().filter(|(_, prob)| **prob > 0.0) {
+            let hit_prob = if matches == config.winning {
+                0.0
+            } else if draw - matches == non_winning {
+                1.0
+            } else {
+                match_rate
+            };
+            if hit_prob > 0.0 {
+                next_probs[matches + 1] += prob * hit_prob;
+            }
+            if hit_prob < 1.0 {
+                next_probs[matches] += prob * (1.0 - hit_prob);
+            }
+        }
+        probs = next_probs;
+    }
+    probs
+}
+
+fn score(scoring: &ScoringRule, matches: usize) -> Result<f64, String> {
+    scoring.score(matches)
+        .map(|points| points as f64)
+        .ok_or(format!("No {} score for {} matches!", scoring.name(), matches))
+}
+
+fn expected_score(match_probs: &[f64], scoring: &ScoringRule) -> Result<f64, String> {
+    match_probs.iter().enumerate()
+        .filter(|(_, prob)| **prob > 0.0)
+        .map(|(matches, prob)| Ok(prob * score(scoring, matches)?))
+        .sum()
+}
+
+// Matches are independent between cards, so by linearity of expectation
+// E[copies of i] = 1 + sum over j < i of P(card j has at least i - j matches) * E[copies of j].
+fn expected_total_cards(match_probs: &[f64], cards: usize) -> f64 {
+    // at_least[d] = P(matches >= d)
+    let mut at_least = vec![0.0; match_probs.len() + 1];
+    for matches in (0..match_probs.len()).rev() {
+        at_least[matches] = at_least[matches + 1] + match_probs[matches];
+    }
+    let mut copies = Vec::<f64>::with_capacity(cards);
+    for card_idx in 0..cards {
+        let won: f64 = (1..at_least.len().min(card_idx + 1))
+            .map(|distance| at_least[distance] * copies[card_idx - distance])
+            .sum();
+        copies.push(1.0 + won);
+    }
+    copies.iter().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        expected::{empirical, expected, hypergeometric, rated_match_probs},
+        generate::GeneratorConfig,
+        scoring::ScoringRule,
+    };
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance * expected.abs().max(1.0), "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_match_probs() {
+        // 2 winning and 2 of our numbers out of 1..=4: C(4,2) = 6 ways to draw ours.
+        let probs = hypergeometric(4, 2, 2);
+        for (actual, expected) in probs.iter().zip([1.0 / 6.0, 4.0 / 6.0, 1.0 / 6.0]) {
+            assert_close(*actual, expected, 1e-12);
+        }
+        let config = GeneratorConfig {winning: 2, ours: 3, max_num: 4, ..GeneratorConfig::default()};
+        // The third draw is forced to match once both non-winning numbers are taken.
+        let probs = rated_match_probs(&config, 0.5);
+        for (actual, expected) in probs.iter().zip([0.0, 0.5, 0.5]) {
+            assert_close(*actual, expected, 1e-12);
+        }
+        assert_close(hypergeometric(1000, 10, 500).iter().sum(), 1.0, 1e-9);
+    }
+
+    #[test]
+    fn test_expected_total_cards() {
+        // Every card matches once, so card i has i copies.
+        let config =
+            GeneratorConfig {cards: 4, winning: 1, ours: 1, match_rate: Some(1.0), ..GeneratorConfig::default()};
+        let expectation = expected(&config, &ScoringRule::Doubling).unwrap();
+        assert_eq!(expectation.match_probs, vec![0.0, 1.0]);
+        assert_close(expectation.score_per_card, 1.0, 1e-12);
+        assert_close(expectation.total_cards, 10.0, 1e-12);
+    }
+
+    #[test]
+    fn test_expected_matches_empirical() {
+        for match_rate in [None, Some(0.1)] {
+            let config = GeneratorConfig {cards: 10, winning: 5, ours: 8, match_rate, max_num: 30, seed: 50};
+            let exact = expected(&config, &ScoringRule::Linear).unwrap();
+            let sampled = empirical(&config, &ScoringRule::Linear, 1000).unwrap();
+            for (actual, expected) in sampled.match_probs.iter().zip(&exact.match_probs) {
+                assert!((actual - expected).abs() < 0.01);
+            }
+            assert_close(sampled.score_per_card, exact.score_per_card, 0.02);
+            assert_close(sampled.total_cards, exact.total_cards, 0.05);
+        }
+        let config = GeneratorConfig {cards: 0, ..GeneratorConfig::default()};
+        assert_eq!(
+            empirical(&config, &ScoringRule::Linear, 10),
+            Err("Need at least one card per sample!".to_string()),
+        );
+    }
+}
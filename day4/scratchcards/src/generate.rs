@@ -0,0 +1,163 @@
+use common::{args::{flag_value, parse_flag}, rng::Rng};
+
+pub const GENERATOR_FLAGS: &[&str] = &["--cards=", "--winning=", "--ours=", "--match-rate=", "--max-num=", "--seed="];
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct GeneratorConfig {
+    pub cards: usize,
+    pub winning: usize,
+    pub ours: usize,
+    // Chance that each of our numbers is drawn from the winning numbers, `None`
+    // draws our numbers uniformly from the whole range.
+    pub match_rate: Option<f64>,
+    // Numbers are drawn from `1..=max_num`.
+    pub max_num: usize,
+    pub seed: u64,
+}
+
+impl Default for GeneratorConfig {
+    // Same shape as the puzzle input.
+    fn default() -> GeneratorConfig {
+        GeneratorConfig {cards: 199, winning: 10, ours: 25, match_rate: Some(0.2), max_num: 99, seed: 0}
+    }
+}
+
+impl GeneratorConfig {
+    // Reads `--cards=`, `--winning=`, `--ours=`, `--match-rate=<rate|uniform>`,
+    // `--max-num=` and `--seed=`, ignoring other args. Missing flags keep the
+    // values of `defaults`.
+    pub fn from_args(args: &[String], defaults: &GeneratorConfig) -> Result<GeneratorConfig, String> {
+        let match_rate = match flag_value(args, "--match-rate")? {
+            None => defaults.match_rate,
+            Some("uniform") => None,
+            Some(_) => parse_flag(args, "--match-rate", 0.0).map(Some)?,
+        };
+        Ok(GeneratorConfig {
+            cards: parse_flag(args, "--cards", defaults.cards)?,
+            winning: parse_flag(args, "--winning", defaults.winning)?,
+            ours: parse_flag(args, "--ours", defaults.ours)?,
+            match_rate,
+            max_num: parse_flag(args, "--max-num", defaults.max_num)?,
+            seed: parse_flag(args, "--seed", defaults.seed)?,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.winning > self.max_num || self.ours > self.max_num {
+            return Err(format!(
+                "Can not draw {} winning and {} of our numbers from 1..={}!",
+                self.winning, self.ours, self.max_num,
+            ));
+        }
+        if let Some(match_rate) = self.match_rate.filter(|match_rate| !(0.0..=1.0).contains(match_rate)) {
+            return Err(format!("Match rate {} must be between 0 and 1!", match_rate));
+        }
+        Ok(())
+    }
+}
+
+// Lines use the puzzle's layout: ids and numbers are right aligned to the
+// width of the largest id and number.
+pub fn generate(config: &GeneratorConfig) -> Result<Vec<String>, String> {
+    config.validate()?;
+    let mut rng = Rng::new(config.seed);
+    let id_width = config.cards.to_string().len();
+    let num_width = config.max_num.to_string().len();
+    let format_nums = |nums: &[usize]| nums.iter()
+        .map(|num| format!("{:>width$}", num, width = num_width))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut lines = Vec::new();
+    for id in 1..=config.cards {
+        let (winning_nums, our_nums) = generate_card(&mut rng, config);
+        lines.push(format!(
+            "Card {:>width$}: {} | {}", id, format_nums(&winning_nums), format_nums(&our_nums), width = id_width,
+        ));
+    }
+    Ok(lines)
+}
+
+fn generate_card(rng: &mut Rng, config: &GeneratorConfig) -> (Vec<usize>, Vec<usize>) {
+    // Partial Fisher-Yates shuffle, the first `winning` numbers are the winning ones.
+    let mut pool: Vec<usize> = (1..=config.max_num).collect();
+    for idx in 0..config.winning {
+        let swap_idx = idx + rng.below(pool.len() - idx);
+        pool.swap(idx, swap_idx);
+    }
+    let mut non_winning = pool.split_off(config.winning);
+    let winning_nums = pool;
+    let mut unmatched = winning_nums.clone();
+    let mut our_nums = Vec::with_capacity(config.ours);
+    for _ in 0..config.ours {
+        let wants_match = match config.match_rate {
+            Some(match_rate) => rng.next_f64() < match_rate,
+            None => rng.below(unmatched.len() + non_winning.len()) < unmatched.len(),
+        };
+        // Falls back to the other kind of number once one of them runs out.
+        let source = if (wants_match && !unmatched.is_empty()) || non_winning.is_empty() {
+            &mut unmatched
+        } else {
+            &mut non_winning
+        };
+        let idx = rng.below(source.len());
+        our_nums.push(source.swap_remove(idx));
+    }
+    (winning_nums, our_nums)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generate::{generate, GeneratorConfig},
+        matching::{count_matches, DuplicatePolicy},
+        strict::{parse_strict, StrictOptions},
+    };
+
+    #[test]
+    fn test_generate_layout() {
+        let config = GeneratorConfig {cards: 12, winning: 3, ours: 4, match_rate: Some(0.5), max_num: 99, seed: 49};
+        let lines = generate(&config).unwrap();
+        assert_eq!(lines.len(), 12);
+        assert!(lines[0].starts_with("Card  1: ") && lines[11].starts_with("Card 12: "));
+        assert!(lines.iter().all(|line| line.len() == "Card 12: 11 22 33 | 11 22 33 44".len()));
+        let cards = parse_strict(&lines.join("\n"), &StrictOptions::default()).unwrap();
+        assert_eq!(cards.len(), 12);
+        assert_eq!(generate(&config), Ok(lines));
+    }
+
+    #[test]
+    fn test_generate_match_rate() {
+        let matches = |match_rate: Option<f64>| {
+            let config = GeneratorConfig {cards: 50, winning: 5, ours: 8, match_rate, max_num: 20, seed: 1};
+            let cards = parse_strict(&generate(&config).unwrap().join("\n"), &StrictOptions::default()).unwrap();
+            cards.iter()
+                .map(|card| count_matches(card, DuplicatePolicy::Reject).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert!(matches(Some(0.0)).iter().all(|count| *count == 0));
+        assert!(matches(Some(1.0)).iter().all(|count| *count == 5));
+        let config = GeneratorConfig {winning: 100, ..GeneratorConfig::default()};
+        assert!(generate(&config).is_err());
+    }
+
+    #[test]
+    fn test_generator_config_from_args() {
+        let to_args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+        let args = to_args(&["--cards=5", "--match-rate=uniform", "--seed=18446744073709551615"]);
+        assert_eq!(
+            GeneratorConfig::from_args(&args, &GeneratorConfig::default()),
+            Ok(GeneratorConfig {cards: 5, match_rate: None, seed: u64::MAX, ..GeneratorConfig::default()}),
+        );
+        assert_eq!(
+            GeneratorConfig::from_args(&to_args(&["--match-rate=0.5"]), &GeneratorConfig::default()),
+            Ok(GeneratorConfig {match_rate: Some(0.5), ..GeneratorConfig::default()}),
+        );
+        assert_eq!(
+            GeneratorConfig::from_args(&to_args(&["--seed=-1"]), &GeneratorConfig::default()),
+            Err("Could not parse --seed=-1!".to_string()),
+        );
+        assert!(GeneratorConfig::from_args(&to_args(&["--cards"]), &GeneratorConfig::default()).is_err());
+        let uniform = GeneratorConfig {match_rate: None, ..GeneratorConfig::default()};
+        assert_eq!(GeneratorConfig::from_args(&[], &uniform), Ok(uniform.clone()));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod card;
+pub mod count;
+pub mod expected;
+pub mod generate;
+pub mod matching;
+pub mod scoring;
+pub mod stream;
+pub mod strict;
@@ -0,0 +1,134 @@
+use std::{collections::{HashMap, HashSet}, iter};
+
+use common::args::flag_value;
+
+use crate::card::Card;
+
+pub const DUPLICATE_FLAGS: &[&str] = &["--duplicates="];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DuplicatePolicy {
+    // Every number counts once, however often it appears on either side.
+    Distinct,
+    // Each of our numbers counts once per occurrence, duplicate winning numbers don't matter.
+    CountOurs,
+    // Every equal (winning, our) pair counts.
+    CountPairs,
+    // Duplicates on either side are an error.
+    Reject,
+}
+
+impl DuplicatePolicy {
+    pub fn from_args(args: &[String]) -> Result<DuplicatePolicy, String> {
+        match flag_value(args, "--duplicates")? {
+            None | Some("distinct") => Ok(DuplicatePolicy::Distinct),
+            Some("ours") => Ok(DuplicatePolicy::CountOurs),
+            Some("pairs") => Ok(DuplicatePolicy::CountPairs),
+            Some("reject") => Ok(DuplicatePolicy::Reject),
+            Some(value) => Err(format!("Unknown duplicate policy '{}'!", value)),
+        }
+    }
+}
+
+// Winning numbers with how often each appears, built once per card.
+pub struct WinningNums {
+    counts: HashMap<usize, usize>,
+}
+
+impl WinningNums {
+    pub fn new(card: &Card) -> WinningNums {
+        let mut counts = HashMap::<usize, usize>::new();
+        for num in &card.winning_nums {
+            *counts.entry(*num).or_insert(0) += 1;
+        }
+        WinningNums {counts}
+    }
+
+    pub fn count(&self, num: usize) -> usize {
+        self.counts.get(&num).copied().unwrap_or(0)
+    }
+}
+
+pub fn count_matches(card: &Card, policy: DuplicatePolicy) -> Result<usize, String> {
+    matched_nums(card, policy).map(|nums| nums.len())
+}
+
+// Our numbers that are winning numbers, repeated as often as the policy counts them.
+pub fn matched_nums(card: &Card, policy: DuplicatePolicy) -> Result<Vec<usize>, String> {
+    let winning = WinningNums::new(card);
+    match policy {
+        DuplicatePolicy::Distinct => {
+            let mut seen = HashSet::<usize>::new();
+            Ok(card.our_nums.iter().copied()
+                .filter(|num| winning.count(*num) > 0 && seen.insert(*num))
+                .collect())
+        }
+        DuplicatePolicy::CountOurs => {
+            Ok(card.our_nums.iter().copied().filter(|num| winning.count(*num) > 0).collect())
+        }
+        DuplicatePolicy::CountPairs => {
+            Ok(card.our_nums.iter().flat_map(|num| iter::repeat_n(*num, winning.count(*num))).collect())
+        }
+        DuplicatePolicy::Reject => {
+            check_distinct(card.id, "winning", &card.winning_nums)?;
+            check_distinct(card.id, "our", &card.our_nums)?;
+            Ok(card.our_nums.iter().copied().filter(|num| winning.count(*num) > 0).collect())
+        }
+    }
+}
+
+// `(id, matches)` of every card in id order, which is the order the copy cascade runs in.
+pub fn matches_by_id(cards: &[Card], policy: DuplicatePolicy) -> Result<Vec<(usize, usize)>, String> {
+    let mut matches = cards.iter()
+        .map(|card| Ok((card.id, count_matches(card, policy)?)))
+        .collect::<Result<Vec<(usize, usize)>, String>>()?;
+    matches.sort_by_key(|(id, _)| *id);
+    Ok(matches)
+}
+
+fn check_distinct(card_id: usize, side: &str, nums: &[usize]) -> Result<(), String> {
+    let mut seen = HashSet::<usize>::new();
+    for num in nums {
+        if !seen.insert(*num) {
+            return Err(format!("Card {}: {} appears more than once among the {} numbers!", card_id, num, side));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{card::Card, matching::{count_matches, matched_nums, matches_by_id, DuplicatePolicy}};
+
+    #[test]
+    fn test_count_matches() {
+        let card = Card {id: 1, winning_nums: vec![41, 48, 83, 86, 17], our_nums: vec![83, 86, 6, 31, 17, 9, 48, 53]};
+        for policy in [DuplicatePolicy::Distinct, DuplicatePolicy::CountOurs, DuplicatePolicy::CountPairs, DuplicatePolicy::Reject] {
+            assert_eq!(count_matches(&card, policy), Ok(4));
+        }
+    }
+
+    #[test]
+    fn test_count_matches_with_duplicates() {
+        let card = Card {id: 3, winning_nums: vec![5, 5, 7], our_nums: vec![5, 7, 7, 9]};
+        assert_eq!(count_matches(&card, DuplicatePolicy::Distinct), Ok(2));
+        assert_eq!(count_matches(&card, DuplicatePolicy::CountOurs), Ok(3));
+        assert_eq!(count_matches(&card, DuplicatePolicy::CountPairs), Ok(4));
+        assert_eq!(matched_nums(&card, DuplicatePolicy::Distinct), Ok(vec![5, 7]));
+        assert_eq!(matched_nums(&card, DuplicatePolicy::CountOurs), Ok(vec![5, 7, 7]));
+        assert_eq!(matched_nums(&card, DuplicatePolicy::CountPairs), Ok(vec![5, 5, 7, 7]));
+        assert_eq!(
+            count_matches(&card, DuplicatePolicy::Reject),
+            Err("Card 3: 5 appears more than once among the winning numbers!".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_matches_by_id() {
+        let cards = vec![
+            Card {id: 4, winning_nums: vec![1, 2], our_nums: vec![2, 1]},
+            Card {id: 2, winning_nums: vec![3], our_nums: vec![4]},
+        ];
+        assert_eq!(matches_by_id(&cards, DuplicatePolicy::Distinct), Ok(vec![(2, 0), (4, 2)]));
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+
+use common::args::flag_value;
+
+pub const SCORING_FLAGS: &[&str] = &["--scoring="];
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ScoringRule {
+    // 0, 1, 2, 4, 8, ... points.
+    Doubling,
+    // One point per match.
+    Linear,
+    // 0, 1, 2, 3, 5, 8, ... points.
+    Fibonacci,
+    // Points for 0, 1, 2, ... matches.
+    Table(Vec<usize>),
+}
+
+impl ScoringRule {
+    // Reads `--scoring=doubling|linear|fibonacci|table:<file>`, defaulting to doubling.
+    pub fn from_args(args: &[String]) -> Result<ScoringRule, String> {
+        match flag_value(args, "--scoring")? {
+            None | Some("doubling") => Ok(ScoringRule::Doubling),
+            Some("linear") => Ok(ScoringRule::Linear),
+            Some("fibonacci") => Ok(ScoringRule::Fibonacci),
+            Some(value) => match value.strip_prefix("table:") {
+                Some(file_path) => {
+                    let text = fs::read_to_string(file_path).map_err(|err| err.to_string())?;
+                    Ok(ScoringRule::Table(parse_table(&text)?))
+                }
+                None => Err(format!("Unknown scoring rule '{}'!", value)),
+            },
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ScoringRule::Doubling => "doubling",
+            ScoringRule::Linear => "linear",
+            ScoringRule::Fibonacci => "fibonacci",
+            ScoringRule::Table(_) => "table",
+        }
+    }
+
+    // `None` if the points overflow or the table has no entry for `matches`.
+    pub fn score(&self, matches: usize) -> Option<usize> {
+        match self {
+            ScoringRule::Doubling if matches == 0 => Some(0),
+            ScoringRule::Doubling => 1usize.checked_shl(u32::try_from(matches - 1).ok()?),
+            ScoringRule::Linear => Some(matches),
+            ScoringRule::Fibonacci if matches == 0 => Some(0),
+            ScoringRule::Fibonacci => {
+                let (mut a, mut b) = (Some(1usize), Some(2usize));
+                for _ in 1..matches {
+                    (a, b) = (b, a.zip(b).and_then(|(a, b)| a.checked_add(b)));
+                }
+                a
+            }
+            ScoringRule::Table(points) => points.get(matches).copied(),
+        }
+    }
+}
+
+// Whitespace separated points for 0, 1, 2, ... matches, `#` starts a comment.
+pub fn parse_table(text: &str) -> Result<Vec<usize>, String> {
+    let mut points = Vec::new();
+    for (line_idx, line) in text.lines().enumerate() {
+        let line = line.split_once('#').map_or(line, |(content, _)| content);
+        for s in line.split_whitespace() {
+            let value = s.parse::<usize>()
+                .map_err(|_err| format!("Line {}: Could not parse points '{}'!", line_idx + 1, s))?;
+            points.push(value);
+        }
+    }
+    if points.is_empty() {
+        return Err("Scoring table is empty!".to_string());
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scoring::{parse_table, ScoringRule};
+
+    #[test]
+    fn test_score() {
+        let scores = |rule: ScoringRule| (0..7).map(|matches| rule.score(matches)).collect::<Vec<_>>();
+        assert_eq!(scores(ScoringRule::Doubling), [0, 1, 2, 4, 8, 16, 32].map(Some));
+        assert_eq!(scores(ScoringRule::Linear), [0, 1, 2, 3, 4, 5, 6].map(Some));
+        assert_eq!(scores(ScoringRule::Fibonacci), [0, 1, 2, 3, 5, 8, 13].map(Some));
+        assert_eq!(ScoringRule::Doubling.score(65), None);
+        assert_eq!(ScoringRule::Fibonacci.score(200), None);
+        assert_eq!(ScoringRule::Table(vec![0, 10, 50]).score(2), Some(50));
+        assert_eq!(ScoringRule::Table(vec![0, 10, 50]).score(3), None);
+    }
+
+    #[test]
+    fn test_parse_table() {
+        assert_eq!(parse_table("0 # no matches\n10 50\n\n100\n"), Ok(vec![0, 10, 50, 100]));
+        assert_eq!(parse_table("0 1\n2 x"), Err("Line 2: Could not parse points 'x'!".to_string()));
+        assert!(parse_table("# nothing\n").is_err());
+    }
+}
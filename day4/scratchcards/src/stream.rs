@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+use crate::{
+    card::{parse_card, Card, IdPolicy}, count::{overflow_error, Counter},
+    matching::{count_matches, DuplicatePolicy},
+};
+
+// Counts cards one at a time. A card only wins copies of the next `matches`
+// cards, so the copies of upcoming cards fit into a ring buffer no longer than
+// the largest match count seen so far. Ids must be sequential.
+pub struct StreamingCounter<C: Counter> {
+    duplicates: DuplicatePolicy,
+    // Copies of the cards after the last pushed one, in id order.
+    upcoming: VecDeque<C>,
+    next_id: usize,
+    total: C,
+}
+
+impl<C: Counter> StreamingCounter<C> {
+    // Cards won by a card are only known once the ids before them are, so out
+    // of order ids can not be streamed.
+    pub fn new(policy: IdPolicy, duplicates: DuplicatePolicy) -> Result<StreamingCounter<C>, String> {
+        if policy != IdPolicy::Sequential {
+            return Err("Streaming needs cards in id order, use --ids=sequential!".to_string());
+        }
+        Ok(StreamingCounter {duplicates, upcoming: VecDeque::new(), next_id: 1, total: C::zero()})
+    }
+
+    pub fn push_line(&mut self, line: &str) -> Result<(), String> {
+        let card = parse_card(line)
+            .ok_or(format!("Line {}: Could not parse card '{}'!", self.next_id, line))?;
+        self.push_card(&card)
+    }
+
+    pub fn push_card(&mut self, card: &Card) -> Result<(), String> {
+        if card.id != self.next_id {
+            return Err(format!(
+                "Line {}: Expected card {}, found card {}!", self.next_id, self.next_id, card.id,
+            ));
+        }
+        let copies = self.upcoming.pop_front().unwrap_or_else(C::one);
+        if !self.total.try_add(&copies) {
+            return Err(overflow_error(card.id));
+        }
+        let matches = count_matches(card, self.duplicates)?;
+        while self.upcoming.len() < matches {
+            self.upcoming.push_back(C::one());
+        }
+        for (offset, won_copies) in self.upcoming.iter_mut().take(matches).enumerate() {
+            if !won_copies.try_add(&copies) {
+                return Err(overflow_error(card.id + offset + 1));
+            }
+        }
+        self.next_id += 1;
+        Ok(())
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.upcoming.len()
+    }
+
+    pub fn total(&self) -> &C {
+        &self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{card::IdPolicy, matching::DuplicatePolicy, stream::StreamingCounter};
+
+    #[test]
+    fn test_streaming_counter() {
+        let mut counter = StreamingCounter::<usize>::new(IdPolicy::Sequential, DuplicatePolicy::Distinct).unwrap();
+        for line in [
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ] {
+            counter.push_line(line).unwrap();
+            assert!(counter.buffered() <= 4);
+        }
+        assert_eq!(*counter.total(), 30);
+        assert_eq!(
+            counter.push_line("Card 8: 1 | 2"),
+            Err("Line 7: Expected card 7, found card 8!".to_string()),
+        );
+        assert!(StreamingCounter::<usize>::new(IdPolicy::Unique, DuplicatePolicy::Distinct).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use common::args::parse_flag;
+
+use crate::card::Card;
+
+pub const STRICT_FLAGS: &[&str] = &["--strict", "--min-num=", "--max-num=", "--allow-duplicates"];
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StrictOptions {
+    pub min_num: usize,
+    pub max_num: usize,
+    pub allow_duplicates: bool,
+}
+
+impl Default for StrictOptions {
+    fn default() -> StrictOptions {
+        StrictOptions {min_num: 1, max_num: 99, allow_duplicates: false}
+    }
+}
+
+impl StrictOptions {
+    // Reads `--min-num=`, `--max-num=` and `--allow-duplicates`, ignoring other args.
+    pub fn from_args(args: &[String]) -> Result<StrictOptions, String> {
+        let defaults = StrictOptions::default();
+        Ok(StrictOptions {
+            min_num: parse_flag(args, "--min-num", defaults.min_num)?,
+            max_num: parse_flag(args, "--max-num", defaults.max_num)?,
+            allow_duplicates: args.iter().any(|arg| arg == "--allow-duplicates"),
+        })
+    }
+}
+
+// Collects every problem instead of stopping at the first one. Columns are
+// 1-based character positions.
+pub fn parse_strict(input: &str, options: &StrictOptions) -> Result<Vec<Card>, Vec<String>> {
+    let mut parser = StrictParser::new(options.clone());
+    let mut cards = Vec::<Card>::new();
+    let mut problems = Vec::new();
+    for line in input.lines() {
+        match parser.parse_line(line) {
+            Ok(card) => cards.push(card),
+            Err(line_problems) => problems.extend(line_problems),
+        }
+    }
+    if problems.is_empty() {
+        Ok(cards)
+    } else {
+        Err(problems)
+    }
+}
+
+// Checks one line at a time, so cards can be validated while streaming.
+// Every card needs as many numbers as the first card that could be parsed.
+pub struct StrictParser {
+    options: StrictOptions,
+    line_num: usize,
+    // Id, winning and our number count of the first card.
+    first: Option<(usize, usize, usize)>,
+}
+
+impl StrictParser {
+    pub fn new(options: StrictOptions) -> StrictParser {
+        StrictParser {options, line_num: 0, first: None}
+    }
+
+    pub fn parse_line(&mut self, line: &str) -> Result<Card, Vec<String>> {
+        self.line_num += 1;
+        let card = parse_card_strict(self.line_num, line, &self.options)?;
+        let (winning_count, our_count) = (card.winning_nums.len(), card.our_nums.len());
+        let (first_id, first_winning_count, first_our_count) =
+            *self.first.get_or_insert((card.id, winning_count, our_count));
+        if (winning_count, our_count) != (first_winning_count, first_our_count) {
+            return Err(vec![format!(
+                "Card {}: Has {} winning and {} of our numbers, but card {} has {} and {}!",
+                card.id, winning_count, our_count, first_id, first_winning_count, first_our_count,
+            )]);
+        }
+        Ok(card)
+    }
+}
+
+fn parse_card_strict(line_num: usize, line: &str, options: &StrictOptions) -> Result<Card, Vec<String>> {
+    let chars: Vec<char> = line.chars().collect();
+    let Some(rest) = line.strip_prefix("Card ") else {
+        return Err(vec![format!("Line {}, column 1: Expected 'Card '!", line_num)]);
+    };
+    let Some(colon_idx) = chars.iter().position(|c| *c == ':') else {
+        return Err(vec![format!("Line {}, column {}: Expected ':'!", line_num, chars.len() + 1)]);
+    };
+    let id_part = &rest[..rest.find(':').unwrap_or(rest.len())];
+    let id_start = 5 + id_part.chars().count() - id_part.trim_start_matches(' ').chars().count();
+    let Ok(id) = id_part.trim_start_matches(' ').parse::<usize>() else {
+        return Err(vec![format!(
+            "Line {}, column {}: Could not parse card id '{}'!", line_num, id_start + 1, id_part.trim(),
+        )]);
+    };
+
+    let mut problems = Vec::new();
+    let mut sides: [Vec<(usize, usize)>; 2] = [Vec::new(), Vec::new()];
+    let mut side = 0;
+    let mut token_start: Option<usize> = None;
+    // A trailing space flushes the last number.
+    for (idx, c) in chars.iter().copied().enumerate().skip(colon_idx + 1).chain([(chars.len(), ' ')]) {
+        if c.is_ascii_digit() {
+            token_start.get_or_insert(idx);
+            continue;
+        }
+        if let Some(start) = token_start.take() {
+            let token: String = chars[start..idx].iter().collect();
+            match token.parse::<usize>() {
+                Ok(num) if num < options.min_num || num > options.max_num => problems.push(format!(
+                    "Card {}, column {}: {} is outside of {}..={}!",
+                    id, start + 1, num, options.min_num, options.max_num,
+                )),
+                Ok(num) => sides[side].push((num, start + 1)),
+                Err(_) => problems.push(format!(
+                    "Card {}, column {}: Could not parse number '{}'!", id, start + 1, token,
+                )),
+            }
+        }
+        match c {
+            ' ' => {}
+            '|' if side == 0 => side = 1,
+            '|' => problems.push(format!("Card {}, column {}: Unexpected second '|'!", id, idx + 1)),
+            _ => problems.push(format!("Card {}, column {}: Unexpected character {:?}!", id, idx + 1, c)),
+        }
+    }
+    if side == 0 {
+        problems.push(format!("Card {}, column {}: Expected '|'!", id, chars.len() + 1));
+    }
+    for (side_name, nums) in ["winning", "our"].iter().zip(&sides) {
+        if nums.is_empty() {
+            problems.push(format!("Card {}: No {} numbers!", id, side_name));
+        }
+        if options.allow_duplicates {
+            continue;
+        }
+        for (num_idx, (num, col)) in nums.iter().enumerate() {
+            if let Some((_, first_col)) = nums[..num_idx].iter().find(|(other, _)| other == num) {
+                problems.push(format!(
+                    "Card {}, column {}: Duplicate {} number {} (first at column {})!",
+                    id, col, side_name, num, first_col,
+                ));
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+    let [winning_nums, our_nums] = sides.map(|nums| nums.into_iter().map(|(num, _)| num).collect());
+    Ok(Card {id, winning_nums, our_nums})
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{card::{parse, Card}, strict::{parse_strict, StrictOptions, StrictParser}};
+
+    #[test]
+    fn test_parse_strict_accepts_aligned_cards() {
+        let input = "Card   1: 41 48  3 | 83  6 48 53\nCard   2: 13 32 20 | 61 30 68 82";
+        assert_eq!(parse_strict(input, &StrictOptions::default()), parse(input).map_err(|err| vec![err]));
+        assert_eq!(
+            parse_strict("Card 1: 1 | 2", &StrictOptions::default()),
+            Ok(vec![Card {id: 1, winning_nums: vec![1], our_nums: vec![2]}]),
+        );
+    }
+
+    #[test]
+    fn test_parse_strict_problems() {
+        let input = [
+            "Card 1: 41 48 | 83 6",
+            "Card 2: 41 41 | 100\t7",
+            "Card 3: 1 2 3 | 4 5",
+            "Card 4: 1 2 4 5",
+            "Cart 5: 1 | 2",
+            "Card x: 1 | 2",
+        ].join("\n");
+        assert_eq!(
+            parse_strict(&input, &StrictOptions::default()),
+            Err(vec![
+                "Card 2, column 17: 100 is outside of 1..=99!".to_string(),
+                "Card 2, column 20: Unexpected character '\\t'!".to_string(),
+                "Card 2, column 12: Duplicate winning number 41 (first at column 9)!".to_string(),
+                "Card 3: Has 3 winning and 2 of our numbers, but card 1 has 2 and 2!".to_string(),
+                "Card 4, column 16: Expected '|'!".to_string(),
+                "Card 4: No our numbers!".to_string(),
+                "Line 5, column 1: Expected 'Card '!".to_string(),
+                "Line 6, column 6: Could not parse card id 'x'!".to_string(),
+            ]),
+        );
+        let options = StrictOptions {allow_duplicates: true, max_num: 100, ..StrictOptions::default()};
+        assert!(parse_strict("Card 2: 41 41 | 100 7", &options).is_ok());
+    }
+
+    #[test]
+    fn test_strict_parser() {
+        let mut parser = StrictParser::new(StrictOptions::default());
+        assert!(parser.parse_line("Card 1 1 | 2").is_err());
+        assert_eq!(parser.parse_line("Card 2: 1 | 2"), Ok(Card {id: 2, winning_nums: vec![1], our_nums: vec![2]}));
+        assert_eq!(
+            parser.parse_line("Card 3: 1 | 2 0"),
+            Err(vec!["Card 3, column 15: 0 is outside of 1..=99!".to_string()]),
+        );
+        assert_eq!(
+            parser.parse_line("Card 4: 1 | 2 3"),
+            Err(vec!["Card 4: Has 1 winning and 2 of our numbers, but card 2 has 1 and 1!".to_string()]),
+        );
+    }
+
+    #[test]
+    fn test_strict_options_from_args() {
+        let args: Vec<String> = ["--strict", "--max-num=200", "--allow-duplicates"].iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            StrictOptions::from_args(&args),
+            Ok(StrictOptions {min_num: 1, max_num: 200, allow_duplicates: true}),
+        );
+        assert!(StrictOptions::from_args(&["--min-num".to_string()]).is_err());
+        assert!(StrictOptions::from_args(&["--max-num=lots".to_string()]).is_err());
+    }
+}